@@ -1,8 +1,8 @@
-use tinyinput::read;
+use tinyinput::{read, read_or};
 
 fn main() {
     let x: i32 = read("Enter integer: ").unwrap();
-    let y: f64 = read("Enter float: ").unwrap_or_default();
+    let y: f64 = read_or("Enter float: ", 0.5).unwrap();
     let s: String = read("Enter string: ").unwrap();
 
     println!("x = {x}, y = {y}, s = {s}");
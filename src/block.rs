@@ -0,0 +1,29 @@
+/// How a multi-line read decides that the block of lines has ended.
+///
+/// The terminating line itself is not part of the block. End-of-input always
+/// ends a block, whatever the terminator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Terminator {
+    /// An empty (or whitespace-only) line.
+    Blank,
+    /// A line containing just `.`.
+    Dot,
+    /// A line containing just the given marker, like a heredoc delimiter.
+    ///
+    /// Surrounding whitespace on the line is ignored when comparing.
+    Marker(String),
+    /// Only the end of the input.
+    Eof,
+}
+
+impl Terminator {
+    /// Whether `line` (without its line ending) ends the block.
+    pub(crate) fn matches(&self, line: &str) -> bool {
+        match self {
+            Terminator::Blank => line.trim().is_empty(),
+            Terminator::Dot => line.trim() == ".",
+            Terminator::Marker(marker) => line.trim() == marker,
+            Terminator::Eof => false,
+        }
+    }
+}
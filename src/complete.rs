@@ -0,0 +1,224 @@
+use std::env;
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// Provides Tab completions for the line editor.
+///
+/// When Tab is pressed while reading from a terminal, the completer is given
+/// the current line and the cursor position (a byte index) and returns the
+/// possible completions. A single candidate is inserted directly. With
+/// several, their longest common prefix is inserted; pressing Tab again then
+/// lists them, and further presses cycle through them.
+///
+/// Set a completer on a prompt with [`Prompt::completer`](crate::Prompt::completer).
+/// Closures taking `(&str, usize)` and returning `Vec<Candidate>` implement
+/// this trait.
+///
+/// ## Example
+///
+/// ```
+/// use tinyinput::{Candidate, Completer};
+///
+/// /// Completes the whole line to a greeting.
+/// struct Greetings;
+///
+/// impl Completer for Greetings {
+///     fn complete(&self, line: &str, _cursor: usize) -> Vec<Candidate> {
+///         ["hello", "hi"]
+///             .iter()
+///             .filter(|word| word.starts_with(line))
+///             .map(|word| Candidate::new(0..line.len(), *word))
+///             .collect()
+///     }
+/// }
+///
+/// let candidates = Greetings.complete("he", 2);
+/// assert_eq!(candidates, [Candidate::new(0..2, "hello")]);
+/// ```
+pub trait Completer {
+    /// Return the completions for `line` with the cursor at byte `cursor`.
+    fn complete(&self, line: &str, cursor: usize) -> Vec<Candidate>;
+}
+
+impl<F> Completer for F
+where
+    F: Fn(&str, usize) -> Vec<Candidate>,
+{
+    fn complete(&self, line: &str, cursor: usize) -> Vec<Candidate> {
+        self(line, cursor)
+    }
+}
+
+/// A possible completion returned by a [`Completer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// The byte range of the line that is replaced, usually the partial word
+    /// before the cursor.
+    pub range: Range<usize>,
+    /// The text that replaces `range`.
+    pub replacement: String,
+    /// The text shown when candidates are listed.
+    pub display: String,
+}
+
+impl Candidate {
+    /// Create a candidate that replaces `range` with `replacement`, and is
+    /// listed as `replacement`.
+    pub fn new(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        let replacement = replacement.into();
+
+        Candidate {
+            range,
+            display: replacement.clone(),
+            replacement,
+        }
+    }
+
+    /// List the candidate as `display` instead of its replacement text.
+    pub fn display(mut self, display: impl Into<String>) -> Self {
+        self.display = display.into();
+        self
+    }
+}
+
+/// Completes the word before the cursor from a fixed list of words.
+///
+/// ## Example
+///
+/// ```
+/// use tinyinput::{Completer, WordCompleter};
+///
+/// let commands = WordCompleter::new(["start", "status", "stop"]);
+/// let candidates = commands.complete("service sta", 11);
+///
+/// let words: Vec<_> = candidates.iter().map(|c| c.replacement.as_str()).collect();
+/// assert_eq!(words, ["start", "status"]);
+/// assert_eq!(candidates[0].range, 8..11);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WordCompleter {
+    words: Vec<String>,
+    ignore_case: bool,
+}
+
+impl WordCompleter {
+    /// Create a completer for `words`, offered in the given order.
+    pub fn new<I>(words: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        WordCompleter {
+            words: words.into_iter().map(Into::into).collect(),
+            ignore_case: false,
+        }
+    }
+
+    /// Match the typed prefix regardless of case.
+    pub fn ignore_case(mut self) -> Self {
+        self.ignore_case = true;
+        self
+    }
+}
+
+impl Completer for WordCompleter {
+    fn complete(&self, line: &str, cursor: usize) -> Vec<Candidate> {
+        let start = word_start(line, cursor);
+        let prefix = &line[start..cursor];
+
+        self.words
+            .iter()
+            .filter(|word| match self.ignore_case {
+                true => word.to_lowercase().starts_with(&prefix.to_lowercase()),
+                false => word.starts_with(prefix),
+            })
+            .map(|word| Candidate::new(start..cursor, word.as_str()))
+            .collect()
+    }
+}
+
+/// Completes the file or directory path before the cursor.
+///
+/// Paths are relative to the current directory unless they are absolute or
+/// start with `~/`. Directories are completed with a trailing `/`, and hidden
+/// entries are only offered when the typed name starts with `.`. Paths are
+/// taken to end at whitespace, so names containing spaces are not completed.
+///
+/// ## Example
+///
+/// ```no_run
+/// use tinyinput::{FilenameCompleter, Prompt};
+///
+/// let path: String = Prompt::new("File: ")
+///     .completer(&FilenameCompleter::new())
+///     .read()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FilenameCompleter {
+    _private: (),
+}
+
+impl FilenameCompleter {
+    /// Create a filename completer.
+    pub fn new() -> Self {
+        FilenameCompleter { _private: () }
+    }
+}
+
+impl Completer for FilenameCompleter {
+    fn complete(&self, line: &str, cursor: usize) -> Vec<Candidate> {
+        let start = word_start(line, cursor);
+        let path = &line[start..cursor];
+
+        let (dir, name) = match path.rfind('/') {
+            Some(index) => path.split_at(index + 1),
+            None => ("", path),
+        };
+
+        let search_dir = match dir {
+            "" => PathBuf::from("."),
+            dir => match (dir.strip_prefix("~/"), env::var_os("HOME")) {
+                (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+                _ => PathBuf::from(dir),
+            },
+        };
+
+        let Ok(entries) = fs::read_dir(search_dir) else {
+            return Vec::new();
+        };
+
+        let mut candidates: Vec<Candidate> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let file_name = entry.file_name().into_string().ok()?;
+                if !file_name.starts_with(name)
+                    || (file_name.starts_with('.') && !name.starts_with('.'))
+                {
+                    return None;
+                }
+
+                let is_dir = entry.path().is_dir();
+                let display = if is_dir {
+                    format!("{}/", file_name)
+                } else {
+                    file_name
+                };
+
+                Some(Candidate::new(start..cursor, format!("{}{}", dir, display)).display(display))
+            })
+            .collect();
+
+        candidates.sort_by(|a, b| a.display.cmp(&b.display));
+        candidates
+    }
+}
+
+/// The byte index where the whitespace-separated word before `cursor` starts.
+fn word_start(line: &str, cursor: usize) -> usize {
+    line[..cursor]
+        .char_indices()
+        .rfind(|(_, c)| c.is_whitespace())
+        .map_or(0, |(index, c)| index + c.len_utf8())
+}
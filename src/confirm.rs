@@ -0,0 +1,202 @@
+use std::io::{BufRead, Write};
+
+use crate::prompt::{with_hint, Retry};
+use crate::{Input, ReadError};
+
+/// A yes/no confirmation prompt.
+///
+/// Answers are matched case-insensitively against lists of "yes" and "no"
+/// words, which default to `y`/`yes` and `n`/`no`. The prompt shows a
+/// `[y/n]` hint, with the default answer (if any) capitalized.
+///
+/// ## Example
+///
+/// ```no_run
+/// use tinyinput::Confirm;
+///
+/// let proceed = Confirm::new("Continue?").default(true).read().unwrap();
+/// ```
+///
+/// Confirmation prompts share the retry and end-of-input behavior of
+/// [`Prompt`](crate::Prompt): unrecognized answers are `ReadError::Parse`
+/// errors that can be re-prompted, and a closed input is `ReadError::Eof`.
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Confirm, Input};
+///
+/// let mut input = Input::new(Cursor::new("maybe\nYes\n"), Vec::new());
+/// let answer = Confirm::new("Continue?").retry().read_from(&mut input).unwrap();
+///
+/// assert!(answer);
+/// assert_eq!(
+///     String::from_utf8_lossy(input.writer()),
+///     "Continue? [y/n] 'maybe' is not valid: expected y/yes or n/no\nContinue? [y/n] ",
+/// );
+/// ```
+pub struct Confirm<'a> {
+    text: &'a str,
+    default: Option<bool>,
+    yes: Vec<String>,
+    no: Vec<String>,
+    retry: Retry,
+}
+
+impl<'a> Confirm<'a> {
+    /// Create a confirmation prompt that prints `text` before reading.
+    pub fn new(text: &'a str) -> Self {
+        Confirm {
+            text,
+            default: None,
+            yes: vec!["y".to_string(), "yes".to_string()],
+            no: vec!["n".to_string(), "no".to_string()],
+            retry: Retry::new(),
+        }
+    }
+
+    /// Answer `value` when the entered line is empty.
+    pub fn default(mut self, value: bool) -> Self {
+        self.default = Some(value);
+        self
+    }
+
+    /// Replace the words accepted as "yes".
+    ///
+    /// Words are matched case-insensitively. The first word is used in the
+    /// prompt hint. Blank words are ignored, and if no words are left the
+    /// current ones are kept.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::{Confirm, Input};
+    ///
+    /// let mut input = Input::new(Cursor::new("ja\n"), Vec::new());
+    /// let answer = Confirm::new("Weiter?")
+    ///     .yes_words(["ja", "j"])
+    ///     .no_words(Vec::<&str>::new())
+    ///     .read_from(&mut input)
+    ///     .unwrap();
+    ///
+    /// assert!(answer);
+    /// assert_eq!(input.writer(), b"Weiter? [ja/n] ");
+    /// ```
+    pub fn yes_words<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words = lowercase(words);
+        if !words.is_empty() {
+            self.yes = words;
+        }
+        self
+    }
+
+    /// Replace the words accepted as "no".
+    ///
+    /// Words are matched case-insensitively. The first word is used in the
+    /// prompt hint. Blank words are ignored, and if no words are left the
+    /// current ones are kept.
+    pub fn no_words<I, S>(mut self, words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words = lowercase(words);
+        if !words.is_empty() {
+            self.no = words;
+        }
+        self
+    }
+
+    /// Re-prompt on unrecognized answers until a valid one is entered.
+    pub fn retry(mut self) -> Self {
+        self.retry.forever();
+        self
+    }
+
+    /// Re-prompt on unrecognized answers, giving up after `attempts` attempts.
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.retry.limit(attempts);
+        self
+    }
+
+    /// Set the message printed before re-prompting.
+    ///
+    /// Any `{error}` in `message` is replaced by the error's `Display` text.
+    pub fn error_message(mut self, message: impl Into<String>) -> Self {
+        self.retry.set_message(message.into());
+        self
+    }
+
+    /// Prompt on `stdout` and read the answer from `stdin`.
+    ///
+    /// ## Errors
+    ///
+    /// - Returns `ReadError::Io` if reading or writing fails.
+    /// - Returns `ReadError::Eof` if `stdin` is closed.
+    /// - Returns `ReadError::Parse` if the answer is not recognized and no
+    ///   attempts are left.
+    pub fn read(&self) -> Result<bool, ReadError> {
+        self.read_from(&mut Input::stdin())
+    }
+
+    /// Prompt and read the answer using an existing [`Input`] handle.
+    ///
+    /// Errors are the same as for [`read`](Confirm::read).
+    pub fn read_from<R, W>(&self, input: &mut Input<R, W>) -> Result<bool, ReadError>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        let text = with_hint(self.text, &self.hint());
+
+        self.retry.run(input, |input| {
+            let line = input.read_line(&text)?;
+            self.parse(line.trim())
+        })
+    }
+
+    fn parse(&self, answer: &str) -> Result<bool, ReadError> {
+        let lower = answer.to_lowercase();
+
+        if lower.is_empty() {
+            if let Some(default) = self.default {
+                return Ok(default);
+            }
+        }
+
+        if self.yes.contains(&lower) {
+            Ok(true)
+        } else if self.no.contains(&lower) {
+            Ok(false)
+        } else {
+            let expected = format!("expected {} or {}", self.yes.join("/"), self.no.join("/"));
+            Err(ReadError::parse(answer, expected))
+        }
+    }
+
+    /// The `y/n` hint, with the default answer capitalized.
+    fn hint(&self) -> String {
+        let yes = self.yes.first().map_or("", String::as_str);
+        let no = self.no.first().map_or("", String::as_str);
+
+        match self.default {
+            Some(true) => format!("{}/{}", yes.to_uppercase(), no),
+            Some(false) => format!("{}/{}", yes, no.to_uppercase()),
+            None => format!("{}/{}", yes, no),
+        }
+    }
+}
+
+fn lowercase<I, S>(words: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    words
+        .into_iter()
+        .map(|word| word.as_ref().trim().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
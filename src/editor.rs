@@ -0,0 +1,871 @@
+use std::borrow::Cow;
+use std::io::{BufRead, Write};
+use std::ops::Range;
+
+use crate::complete::{Candidate, Completer};
+use crate::hint::Hinter;
+use crate::history::DEFAULT_NAMESPACE;
+use crate::keys::{read_key, Key};
+use crate::term::{self, RawMode, Signal};
+use crate::unicode::{next_boundary, prev_boundary, width};
+use crate::ReadError;
+
+/// Per-prompt settings for the line editor.
+#[derive(Clone, Copy)]
+pub(crate) struct LineOptions<'a> {
+    /// The history namespace, or `None` to keep lines out of the history.
+    pub(crate) history: Option<&'a str>,
+    pub(crate) completer: Option<&'a dyn Completer>,
+    pub(crate) hinter: Option<&'a dyn Hinter>,
+}
+
+impl Default for LineOptions<'_> {
+    fn default() -> Self {
+        LineOptions {
+            history: Some(DEFAULT_NAMESPACE),
+            completer: None,
+            hinter: None,
+        }
+    }
+}
+
+/// An Emacs-style line editor used when reading from a terminal.
+///
+/// The buffer is redrawn in place after every key, wrapping over several
+/// terminal rows if needed. The cursor always sits on a grapheme boundary.
+pub(crate) struct Editor<'e> {
+    /// The part of the prompt after its last newline, redrawn on refresh.
+    prompt: &'e str,
+    /// Earlier lines, oldest first.
+    history: Vec<&'e str>,
+    buffer: String,
+    /// Byte index of the cursor in `buffer`.
+    cursor: usize,
+    /// Text removed by the last kill command(s), inserted again by yank.
+    kill_ring: String,
+    /// Whether the previous key was a kill, so the next one appends to it.
+    killing: bool,
+    /// Index into `history` while browsing it, and the line being edited
+    /// before browsing started.
+    browsing: Option<(usize, String)>,
+    /// The reverse incremental search in progress, if any.
+    search: Option<Search>,
+    completer: Option<&'e dyn Completer>,
+    hinter: Option<&'e dyn Hinter>,
+    /// The candidates of the previous key, if it was Tab.
+    completion: Option<Completion>,
+    /// Terminal row of the cursor after the last refresh, relative to the
+    /// prompt.
+    cursor_row: usize,
+}
+
+/// Candidates offered by consecutive presses of Tab.
+struct Completion {
+    candidates: Vec<Candidate>,
+    /// The line and cursor the candidates were computed for.
+    original: (String, usize),
+    /// Whether the candidates were listed, so the next Tab cycles.
+    listed: bool,
+    /// The candidate currently inserted while cycling.
+    index: Option<usize>,
+}
+
+/// State of a Ctrl-R reverse incremental search.
+struct Search {
+    query: String,
+    /// Index into `history` of the current match.
+    found: Option<usize>,
+    /// Whether the last search step found nothing.
+    failed: bool,
+    /// The line and cursor before the search, restored by Ctrl-G.
+    saved: (String, usize),
+}
+
+impl<'e> Editor<'e> {
+    pub(crate) fn new(prompt: &'e str, history: Vec<&'e str>, options: LineOptions<'e>) -> Self {
+        Editor {
+            prompt,
+            history,
+            buffer: String::new(),
+            cursor: 0,
+            kill_ring: String::new(),
+            killing: false,
+            browsing: None,
+            search: None,
+            completer: options.completer,
+            hinter: options.hinter,
+            completion: None,
+            cursor_row: 0,
+        }
+    }
+
+    /// Print the prompt, edit a line while the terminal is in `raw` mode,
+    /// and return it with a trailing newline, like [`BufRead::read_line`].
+    pub(crate) fn read_line<R, W>(
+        mut self,
+        reader: &mut R,
+        writer: &mut W,
+        raw: &RawMode,
+    ) -> Result<String, ReadError>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        // Lines before the last newline are printed once and never redrawn.
+        if let Some(end) = self.prompt.rfind('\n') {
+            write!(writer, "{}", &self.prompt[..=end]).map_err(ReadError::Io)?;
+            self.prompt = &self.prompt[end + 1..];
+        }
+
+        let result = self.run(reader, writer, raw);
+
+        write!(writer, "\r\n").map_err(ReadError::Io)?;
+        writer.flush().map_err(ReadError::Io)?;
+        result
+    }
+
+    fn run<R, W>(
+        &mut self,
+        reader: &mut R,
+        writer: &mut W,
+        raw: &RawMode,
+    ) -> Result<String, ReadError>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        loop {
+            self.refresh(writer)?;
+
+            // End-of-input after some text accepts the line, like Enter.
+            let key = match read_key(reader, raw).map_err(ReadError::Io)? {
+                Some(key) => key,
+                None if self.buffer.is_empty() => return Err(ReadError::Eof),
+                None => Key::Enter,
+            };
+
+            let key = match self.search {
+                Some(_) => match self.search_key(key) {
+                    Some(key) => key,
+                    None => continue,
+                },
+                None => key,
+            };
+
+            let was_killing = std::mem::replace(&mut self.killing, false);
+            let completion = self.completion.take();
+
+            match key {
+                Key::Enter => {
+                    // Redraw the accepted line without a hint.
+                    self.hinter = None;
+                    self.cursor = self.buffer.len();
+                    self.refresh(writer)?;
+                    return Ok(std::mem::take(&mut self.buffer) + "\n");
+                }
+                Key::Ctrl('c') => return Err(ReadError::Interrupted),
+                Key::Ctrl('d') if self.buffer.is_empty() => return Err(ReadError::Eof),
+
+                Key::Right | Key::Ctrl('f') | Key::End | Key::Ctrl('e')
+                    if self.cursor == self.buffer.len() =>
+                {
+                    if let Some(hint) = self.hint() {
+                        self.insert(&hint);
+                    }
+                }
+                Key::Left | Key::Ctrl('b') => {
+                    self.cursor = prev_boundary(&self.buffer, self.cursor)
+                }
+                Key::Right | Key::Ctrl('f') => {
+                    self.cursor = next_boundary(&self.buffer, self.cursor)
+                }
+                Key::Home | Key::Ctrl('a') => self.cursor = 0,
+                Key::End | Key::Ctrl('e') => self.cursor = self.buffer.len(),
+                Key::CtrlLeft | Key::Alt('b') => self.cursor = self.word_start(),
+                Key::CtrlRight | Key::Alt('f') => self.cursor = self.word_end(),
+
+                Key::Backspace => {
+                    let start = prev_boundary(&self.buffer, self.cursor);
+                    self.buffer.replace_range(start..self.cursor, "");
+                    self.cursor = start;
+                }
+                Key::Delete | Key::Ctrl('d') => {
+                    let end = next_boundary(&self.buffer, self.cursor);
+                    self.buffer.replace_range(self.cursor..end, "");
+                }
+
+                Key::Ctrl('k') => self.kill(self.cursor, self.buffer.len(), was_killing),
+                Key::Ctrl('u') => self.kill(0, self.cursor, was_killing),
+                Key::Ctrl('w') | Key::Alt('\x7f') => {
+                    self.kill(self.word_start(), self.cursor, was_killing)
+                }
+                Key::Alt('d') => self.kill(self.cursor, self.word_end(), was_killing),
+                Key::Ctrl('y') => {
+                    let text = self.kill_ring.clone();
+                    self.insert(&text);
+                }
+
+                Key::Up | Key::Ctrl('p') => self.history_prev(),
+                Key::Down | Key::Ctrl('n') => self.history_next(),
+                Key::Ctrl('r') => {
+                    self.search = Some(Search {
+                        query: String::new(),
+                        found: None,
+                        failed: false,
+                        saved: (self.buffer.clone(), self.cursor),
+                    });
+                }
+
+                Key::Ctrl('l') => {
+                    write!(writer, "\x1b[H\x1b[2J").map_err(ReadError::Io)?;
+                    self.cursor_row = 0;
+                }
+                Key::Ctrl('z') | Key::Ctrl('\\') => {
+                    let signal = match key {
+                        Key::Ctrl('z') => Signal::Stop,
+                        _ => Signal::Quit,
+                    };
+                    raw.raise(signal).map_err(ReadError::Io)?;
+                    // The shell has printed below the line; redraw it there.
+                    self.cursor_row = 0;
+                }
+                Key::Tab => self.complete(completion, writer)?,
+                Key::Char(c) => self.insert(c.encode_utf8(&mut [0; 4])),
+                _ => {}
+            }
+        }
+    }
+
+    fn insert(&mut self, text: &str) {
+        self.buffer.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    /// Remove `start..end` into the kill ring, appending or prepending to
+    /// the previous kill if `append` is set.
+    fn kill(&mut self, start: usize, end: usize, append: bool) {
+        let text: String = self.buffer.drain(start..end).collect();
+
+        if !append {
+            self.kill_ring.clear();
+        }
+        if start < self.cursor {
+            self.kill_ring.insert_str(0, &text);
+        } else {
+            self.kill_ring.push_str(&text);
+        }
+
+        self.cursor = start;
+        self.killing = true;
+    }
+
+    /// The start of the word before the cursor.
+    fn word_start(&self) -> usize {
+        let before = self.buffer[..self.cursor].trim_end();
+        before
+            .char_indices()
+            .rfind(|(_, c)| c.is_whitespace())
+            .map_or(0, |(index, c)| index + c.len_utf8())
+    }
+
+    /// The end of the word after the cursor.
+    fn word_end(&self) -> usize {
+        let after = &self.buffer[self.cursor..];
+        let skipped = after.len() - after.trim_start().len();
+        let rest = &after[skipped..];
+
+        self.cursor + skipped + rest.find(char::is_whitespace).unwrap_or(rest.len())
+    }
+
+    fn history_prev(&mut self) {
+        let index = match &self.browsing {
+            Some((0, _)) => return,
+            Some((index, _)) => index - 1,
+            None if self.history.is_empty() => return,
+            None => self.history.len() - 1,
+        };
+
+        let saved = match self.browsing.take() {
+            Some((_, saved)) => saved,
+            None => self.buffer.clone(),
+        };
+        self.browsing = Some((index, saved));
+        self.set_buffer(self.history[index].to_string());
+    }
+
+    fn history_next(&mut self) {
+        match self.browsing.take() {
+            Some((index, saved)) if index + 1 < self.history.len() => {
+                self.browsing = Some((index + 1, saved));
+                self.set_buffer(self.history[index + 1].to_string());
+            }
+            Some((_, saved)) => self.set_buffer(saved),
+            None => {}
+        }
+    }
+
+    /// Complete the line on Tab. `previous` holds the candidates if the
+    /// previous key was also Tab.
+    fn complete<W: Write>(
+        &mut self,
+        previous: Option<Completion>,
+        writer: &mut W,
+    ) -> Result<(), ReadError> {
+        let mut completion = match previous {
+            Some(completion) => completion,
+            None => {
+                let Some(completer) = self.completer else {
+                    return Ok(());
+                };
+
+                let line = &self.buffer;
+                let mut candidates = completer.complete(line, self.cursor);
+                candidates.retain(|candidate| {
+                    let Range { start, end } = candidate.range;
+                    start <= end && line.get(start..end).is_some()
+                });
+
+                match candidates.len() {
+                    0 => return writer.write_all(b"\x07").map_err(ReadError::Io),
+                    1 => {
+                        let candidate = &candidates[0];
+                        self.replace(candidate.range.clone(), &candidate.replacement);
+                        return Ok(());
+                    }
+                    _ => {}
+                }
+
+                let completion = Completion {
+                    candidates,
+                    original: (self.buffer.clone(), self.cursor),
+                    listed: false,
+                    index: None,
+                };
+
+                // Insert the common prefix first, and list only if that
+                // does not add anything.
+                if let Some((range, prefix)) = common_prefix(&completion.candidates) {
+                    if prefix.len() > range.len() {
+                        self.replace(range, &prefix);
+                        self.completion = Some(completion);
+                        return Ok(());
+                    }
+                }
+
+                completion
+            }
+        };
+
+        if completion.listed {
+            let index = completion
+                .index
+                .map_or(0, |index| (index + 1) % completion.candidates.len());
+            let candidate = &completion.candidates[index];
+
+            (self.buffer, self.cursor) = completion.original.clone();
+            self.replace(candidate.range.clone(), &candidate.replacement);
+            completion.index = Some(index);
+        } else {
+            self.list(&completion.candidates, writer)?;
+            completion.listed = true;
+        }
+
+        self.completion = Some(completion);
+        Ok(())
+    }
+
+    /// The hint to show after the line, if the cursor is at its end.
+    fn hint(&self) -> Option<String> {
+        let hinter = self.hinter?;
+
+        if self.search.is_some() || self.cursor < self.buffer.len() {
+            return None;
+        }
+
+        hinter
+            .hint(&self.buffer, &self.history)
+            .filter(|hint| !hint.is_empty())
+    }
+
+    fn replace(&mut self, range: Range<usize>, text: &str) {
+        self.cursor = range.start + text.len();
+        self.buffer.replace_range(range, text);
+    }
+
+    /// Print candidates in columns below the line, like bash.
+    fn list<W: Write>(
+        &mut self,
+        candidates: &[Candidate],
+        writer: &mut W,
+    ) -> Result<(), ReadError> {
+        // Move below the end of the line first.
+        let cursor = self.cursor;
+        self.cursor = self.buffer.len();
+        self.refresh(writer)?;
+        self.cursor = cursor;
+
+        let (cols, _) = term::size();
+        let column_width = candidates
+            .iter()
+            .map(|c| width(&c.display))
+            .max()
+            .unwrap_or(0)
+            + 2;
+        let columns = (cols / column_width).max(1);
+        let rows = (candidates.len() + columns - 1) / columns;
+
+        let mut out = String::from("\r\n");
+        for row in 0..rows {
+            for column in 0..columns {
+                let Some(candidate) = candidates.get(column * rows + row) else {
+                    break;
+                };
+                out.push_str(&candidate.display);
+                if candidates.get((column + 1) * rows + row).is_some() {
+                    let padding = column_width - width(&candidate.display);
+                    out.push_str(&" ".repeat(padding));
+                }
+            }
+            out.push_str("\r\n");
+        }
+
+        self.cursor_row = 0;
+        writer.write_all(out.as_bytes()).map_err(ReadError::Io)
+    }
+
+    /// Handle a key during a reverse search.
+    ///
+    /// Returns the key if it ends the search and should then be handled as
+    /// usual, like in bash: Enter accepts the match, and movement keys
+    /// leave it in the buffer for editing.
+    fn search_key(&mut self, key: Key) -> Option<Key> {
+        let search = self.search.as_mut()?;
+        let newest = self.history.len().checked_sub(1);
+
+        match key {
+            Key::Char(c) => {
+                // The current match may still contain the longer query.
+                let start = search.found.or(newest);
+                search.query.push(c);
+                self.search_from(start);
+            }
+            Key::Backspace => {
+                search.query.pop();
+                if search.query.is_empty() {
+                    search.failed = false;
+                } else {
+                    self.search_from(newest);
+                }
+            }
+            Key::Ctrl('r') => {
+                let older = match search.found {
+                    Some(index) => index.checked_sub(1),
+                    None => newest,
+                };
+                match older {
+                    Some(_) => self.search_from(older),
+                    None => search.failed = true,
+                }
+            }
+            Key::Ctrl('g') => {
+                let (buffer, cursor) = self.search.take()?.saved;
+                self.buffer = buffer;
+                self.cursor = cursor;
+            }
+            Key::Esc => self.search = None,
+            key => {
+                self.search = None;
+                return Some(key);
+            }
+        }
+
+        None
+    }
+
+    /// Find the newest history entry at or before `start` that contains the
+    /// query, and show it with the cursor at the match.
+    fn search_from(&mut self, start: Option<usize>) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+
+        let found = start.and_then(|start| {
+            (0..=start).rev().find_map(|index| {
+                let offset = self.history[index].find(&search.query)?;
+                Some((index, offset))
+            })
+        });
+
+        match found {
+            Some((index, offset)) => {
+                search.found = Some(index);
+                search.failed = false;
+                self.buffer = self.history[index].to_string();
+                self.cursor = offset;
+                self.browsing = None;
+            }
+            None => search.failed = true,
+        }
+    }
+
+    fn set_buffer(&mut self, text: String) {
+        self.buffer = text;
+        self.cursor = self.buffer.len();
+    }
+
+    /// Redraw the prompt and buffer, and place the cursor.
+    fn refresh<W: Write>(&mut self, writer: &mut W) -> Result<(), ReadError> {
+        let (cols, _) = term::size();
+        let prompt = match &self.search {
+            Some(search) => Cow::Owned(format!(
+                "({}reverse-i-search)`{}': ",
+                if search.failed { "failed " } else { "" },
+                search.query,
+            )),
+            None => Cow::Borrowed(self.prompt),
+        };
+        let prompt_width = width(&prompt);
+        let cursor_pos = prompt_width + width(&self.buffer[..self.cursor]);
+        let hint = self.hint().unwrap_or_default();
+        let end_pos = prompt_width + width(&self.buffer) + width(&hint);
+
+        let mut out = String::new();
+
+        if self.cursor_row > 0 {
+            out.push_str(&format!("\x1b[{}A", self.cursor_row));
+        }
+        out.push_str("\r\x1b[J");
+        out.push_str(&prompt);
+        out.push_str(&self.buffer);
+        if !hint.is_empty() {
+            match term::color_enabled() {
+                true => out.push_str(&format!("\x1b[2m{}\x1b[22m", hint)),
+                false => out.push_str(&hint),
+            }
+        }
+
+        // At the exact end of a row the terminal has not wrapped yet.
+        if end_pos > 0 && end_pos % cols == 0 {
+            out.push_str("\r\n");
+        }
+
+        let (end_row, cursor_row, cursor_col) =
+            (end_pos / cols, cursor_pos / cols, cursor_pos % cols);
+        if end_row > cursor_row {
+            out.push_str(&format!("\x1b[{}A", end_row - cursor_row));
+        }
+        out.push('\r');
+        if cursor_col > 0 {
+            out.push_str(&format!("\x1b[{}C", cursor_col));
+        }
+
+        self.cursor_row = cursor_row;
+        writer.write_all(out.as_bytes()).map_err(ReadError::Io)?;
+        writer.flush().map_err(ReadError::Io)
+    }
+}
+
+/// The longest common prefix of the candidates' replacements, if they all
+/// replace the same range.
+fn common_prefix(candidates: &[Candidate]) -> Option<(Range<usize>, String)> {
+    let (first, rest) = candidates.split_first()?;
+    let mut prefix = first.replacement.as_str();
+
+    for candidate in rest {
+        if candidate.range != first.range {
+            return None;
+        }
+
+        let len = prefix
+            .char_indices()
+            .zip(candidate.replacement.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(
+                prefix.len().min(candidate.replacement.len()),
+                |((index, _), _)| index,
+            );
+        prefix = &prefix[..len];
+    }
+
+    Some((first.range.clone(), prefix.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{HistoryHinter, WordCompleter};
+
+    const UP: &str = "\x1b[A";
+    const DOWN: &str = "\x1b[B";
+    const LEFT: &str = "\x1b[D";
+    const RIGHT: &str = "\x1b[C";
+    const END: &str = "\x1b[F";
+
+    /// Edit a line by feeding `keys` to the editor, with `history` to browse.
+    fn edit(history: &[&str], keys: &str) -> Result<String, ReadError> {
+        edit_with(history, LineOptions::default(), keys).0
+    }
+
+    /// Like [`edit`], with `options`, also returning what was printed.
+    fn edit_with(
+        history: &[&str],
+        options: LineOptions<'_>,
+        keys: &str,
+    ) -> (Result<String, ReadError>, String) {
+        let mut reader = Cursor::new(keys.as_bytes());
+        let mut output = Vec::new();
+
+        let result = Editor::new("> ", history.to_vec(), options).read_line(
+            &mut reader,
+            &mut output,
+            &RawMode::inert(),
+        );
+
+        (result, String::from_utf8_lossy(&output).into_owned())
+    }
+
+    /// Edit a line with Tab completing from `completer`.
+    fn complete(completer: &dyn Completer, keys: &str) -> (String, String) {
+        let options = LineOptions {
+            completer: Some(completer),
+            ..LineOptions::default()
+        };
+        let (result, output) = edit_with(&[], options, keys);
+
+        (result.unwrap(), output)
+    }
+
+    #[test]
+    fn consecutive_kills_are_yanked_together() {
+        // Ctrl-W twice prepends the second word to the first.
+        let line = edit(&[], "one two three\x17\x17X\x19\r").unwrap();
+        assert_eq!(line, "one Xtwo three\n");
+
+        // Alt-D twice appends the second word to the first.
+        let line = edit(&[], "abc def\x01\x1bd\x1bd\x05\x19\r").unwrap();
+        assert_eq!(line, "abc def\n");
+
+        // Ctrl-K then Ctrl-U keeps both halves in order.
+        let keys = format!("abcd{}{}\x0b\x15\x19\r", LEFT, LEFT);
+        assert_eq!(edit(&[], &keys).unwrap(), "abcd\n");
+    }
+
+    #[test]
+    fn other_keys_start_a_new_kill() {
+        let line = edit(&[], "ab\x15cd\x15\x19\r").unwrap();
+        assert_eq!(line, "cd\n");
+    }
+
+    #[test]
+    fn history_is_browsed_and_the_line_restored() {
+        let history = ["first", "second"];
+
+        let keys = format!("draft{}{}\r", UP, UP);
+        assert_eq!(edit(&history, &keys).unwrap(), "first\n");
+
+        // Up stops at the oldest entry, and Down past the newest entry
+        // restores the line being edited.
+        let keys = format!("draft{}{}{}{}\r", UP, UP, UP, DOWN);
+        assert_eq!(edit(&history, &keys).unwrap(), "second\n");
+
+        let keys = format!("draft{}{}{}{}{}\r", UP, UP, UP, DOWN, DOWN);
+        assert_eq!(edit(&history, &keys).unwrap(), "draft\n");
+
+        let line = edit(&history, "draft\x10\x0e\x0e\r").unwrap();
+        assert_eq!(line, "draft\n");
+    }
+
+    #[test]
+    fn reverse_search_finds_older_matches() {
+        let history = ["make", "cargo build", "cargo test", "ls"];
+
+        assert_eq!(edit(&history, "\x12cargo\r").unwrap(), "cargo test\n");
+        assert_eq!(edit(&history, "\x12cargo\x12\r").unwrap(), "cargo build\n");
+
+        // Without an older match, the search fails and keeps the last one.
+        assert_eq!(
+            edit(&history, "\x12cargo\x12\x12\r").unwrap(),
+            "cargo build\n"
+        );
+
+        // Backspace searches for the shorter query from the newest entry.
+        assert_eq!(edit(&history, "\x12cargo b\x7f\r").unwrap(), "cargo test\n");
+        assert_eq!(edit(&history, "\x12xyz\r").unwrap(), "\n");
+    }
+
+    #[test]
+    fn reverse_search_ends_on_other_keys() {
+        let history = ["make", "cargo build", "cargo test"];
+
+        // Ctrl-G restores the line from before the search.
+        assert_eq!(edit(&history, "draft\x12cargo\x07\r").unwrap(), "draft\n");
+
+        // Escape keeps the match; a lone Escape must be the last key here.
+        assert_eq!(edit(&history, "\x12build\x1b").unwrap(), "cargo build\n");
+
+        // Movement keys keep the match and are then handled as usual.
+        assert_eq!(
+            edit(&history, "\x12build\x05!\r").unwrap(),
+            "cargo build!\n"
+        );
+        assert_eq!(edit(&history, "draft\x12make\x01#\r").unwrap(), "#make\n");
+    }
+
+    #[test]
+    fn suspending_keeps_the_line() {
+        assert_eq!(edit(&[], "ab\x1acd\r").unwrap(), "abcd\n");
+        assert_eq!(edit(&[], "ab\x1ccd\r").unwrap(), "abcd\n");
+    }
+
+    #[test]
+    fn tab_inserts_the_common_prefix() {
+        let commands = WordCompleter::new(["start", "status", "stop"]);
+
+        assert_eq!(complete(&commands, "s\t\r").0, "st\n");
+        assert_eq!(complete(&commands, "sto\t\r").0, "stop\n");
+        assert_eq!(complete(&commands, "go sta\t\r").0, "go sta\n");
+    }
+
+    #[test]
+    fn second_tab_lists_and_later_tabs_cycle() {
+        let commands = WordCompleter::new(["start", "status", "stop"]);
+
+        let (line, output) = complete(&commands, "s\t\t\r");
+        assert_eq!(line, "st\n");
+        assert!(output.contains("\r\nstart   status  stop\r\n"));
+
+        // Without a longer common prefix, the first Tab lists.
+        let (line, output) = complete(&commands, "sta\t\r");
+        assert_eq!(line, "sta\n");
+        assert!(output.contains("\r\nstart   status\r\n"));
+
+        // Cycling replaces the text the candidates were computed for.
+        assert_eq!(complete(&commands, "s\t\t\t\r").0, "start\n");
+        assert_eq!(complete(&commands, "s\t\t\t\t\r").0, "status\n");
+        assert_eq!(complete(&commands, "s\t\t\t\t\t\r").0, "stop\n");
+        assert_eq!(complete(&commands, "s\t\t\t\t\t\t\r").0, "start\n");
+
+        // Any other key ends the completion.
+        assert_eq!(complete(&commands, "s\t\t\tx\t\r").0, "startx\n");
+    }
+
+    #[test]
+    fn tab_beeps_without_candidates() {
+        let commands = WordCompleter::new(["start", "stop"]);
+
+        let (line, output) = complete(&commands, "x\t\r");
+        assert_eq!(line, "x\n");
+        assert!(output.contains('\x07'));
+
+        let (_, output) = complete(&commands, "s\t\r");
+        assert!(!output.contains('\x07'));
+    }
+
+    #[test]
+    fn candidates_outside_the_line_are_dropped() {
+        let completer = |_: &str, _: usize| {
+            vec![
+                Candidate::new(0..10, "too long"),
+                Candidate::new(Range { start: 2, end: 1 }, "backwards"),
+                Candidate::new(0..1, "inside a character"),
+                Candidate::new(0..2, "é!"),
+            ]
+        };
+
+        assert_eq!(complete(&completer, "é\t\r").0, "é!\n");
+    }
+
+    /// Edit a line with hints from `history`, returning the line and every
+    /// frame drawn.
+    fn hinted(history: &[&str], keys: &str) -> (String, Vec<String>) {
+        let hinter = HistoryHinter::new();
+        let options = LineOptions {
+            hinter: Some(&hinter),
+            ..LineOptions::default()
+        };
+        let (result, output) = edit_with(history, options, keys);
+        let frames = output.split("\r\x1b[J").map(str::to_string).collect();
+
+        (result.unwrap(), frames)
+    }
+
+    #[test]
+    fn right_and_end_accept_the_hint() {
+        let history = ["cargo build --release"];
+
+        assert_eq!(
+            hinted(&history, &format!("car{}\r", RIGHT)).0,
+            "cargo build --release\n"
+        );
+        assert_eq!(
+            hinted(&history, &format!("car{}\r", END)).0,
+            "cargo build --release\n"
+        );
+        assert_eq!(hinted(&history, "car\x05\r").0, "cargo build --release\n");
+        assert_eq!(hinted(&history, "car\r").0, "car\n");
+    }
+
+    #[test]
+    fn hints_are_only_shown_at_the_end_of_the_line() {
+        let history = ["cargo build"];
+
+        // Frames: "", "c", "ca", "car", Left, Right, Enter.
+        let keys = format!("car{}{}\r", LEFT, RIGHT);
+        let (line, frames) = hinted(&history, &keys);
+        assert_eq!(line, "car\n");
+        assert!(frames[4].contains("go build"));
+        assert!(!frames[5].contains("go build"));
+        assert!(frames[6].contains("go build"));
+        assert!(!frames[7].contains("go build"));
+
+        // Right or End in the middle of the line only move the cursor.
+        let keys = format!("car{}{}{}\r", LEFT, LEFT, END);
+        assert_eq!(hinted(&history, &keys).0, "car\n");
+    }
+
+    #[test]
+    fn hints_are_not_shown_during_reverse_search() {
+        let history = ["cargo build --release", "cargo"];
+
+        let (line, frames) = hinted(&history, "\x12go\r");
+        assert_eq!(line, "cargo\n");
+        assert!(frames.iter().all(|frame| !frame.contains("--release")));
+
+        // Ctrl-R with an empty query leaves the cursor at the end of the
+        // line, where the hint is shown again after Ctrl-G.
+        let (line, frames) = hinted(&history, "cargo\x12\x07\r");
+        assert_eq!(line, "cargo\n");
+        assert!(frames[6].contains(" build --release"));
+        assert!(!frames[7].contains(" build --release"));
+        assert!(frames[8].contains(" build --release"));
+    }
+
+    #[test]
+    fn hints_are_dimmed_unless_no_color_is_set() {
+        let history = ["cargo build"];
+        let original = std::env::var_os("NO_COLOR");
+
+        std::env::remove_var("NO_COLOR");
+        let (_, frames) = hinted(&history, "car");
+        assert!(frames[4].ends_with("> car\x1b[2mgo build\x1b[22m\r\x1b[5C"));
+
+        std::env::set_var("NO_COLOR", "1");
+        let (_, frames) = hinted(&history, "car");
+        assert!(frames[4].ends_with("> cargo build\r\x1b[5C"));
+
+        match original {
+            Some(value) => std::env::set_var("NO_COLOR", value),
+            None => std::env::remove_var("NO_COLOR"),
+        }
+    }
+
+    #[test]
+    fn end_of_input() {
+        assert_eq!(edit(&[], "partial").unwrap(), "partial\n");
+        assert!(matches!(edit(&[], ""), Err(ReadError::Eof)));
+        assert!(matches!(edit(&[], "\x04"), Err(ReadError::Eof)));
+        assert!(matches!(edit(&[], "abc\x03"), Err(ReadError::Interrupted)));
+    }
+}
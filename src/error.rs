@@ -0,0 +1,185 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Errors that can occur while reading or parsing user input.
+///
+/// ## Example
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Input, ReadError};
+///
+/// let mut input = Input::new(Cursor::new("12a\n"), std::io::sink());
+/// let err = input.read::<i32>("Number: ").unwrap_err();
+///
+/// assert!(matches!(&err, ReadError::Parse { input, .. } if input == "12a"));
+/// assert_eq!(err.to_string(), "'12a' is not valid: invalid digit found in string");
+/// ```
+///
+/// New variants may be added in later versions, so a `match` on this enum
+/// needs a wildcard arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ReadError {
+    /// An I/O error occurred while reading input or writing the prompt.
+    Io(io::Error),
+    /// The input could not be parsed into the requested type.
+    Parse {
+        /// The trimmed text that failed to parse.
+        input: String,
+        /// The error returned by the type's `FromStr` implementation.
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// The input was parsed but rejected by a validator.
+    ///
+    /// The message describes why the value is not acceptable.
+    Invalid(String),
+    /// A single value inside a larger input failed.
+    ///
+    /// Used when a line holds several values, to report which one could not
+    /// be read.
+    At {
+        /// Where the failing value is.
+        position: Position,
+        /// Why it failed.
+        source: Box<ReadError>,
+    },
+    /// The input did not match the pattern passed to [`scan`](crate::scan).
+    Mismatch {
+        /// The 1-based character column where matching failed.
+        column: usize,
+        /// What the pattern expected at that column, such as `'from'`,
+        /// `a value` or `end of line`.
+        expected: String,
+        /// The rest of the line from that column on.
+        found: String,
+    },
+    /// Reading was cancelled with Ctrl-C while the terminal was in raw mode.
+    ///
+    /// Raw mode turns Ctrl-C into a key press instead of a signal, so the
+    /// terminal can be restored before this error is returned.
+    Interrupted,
+    /// The input reached end-of-file before a line could be read.
+    ///
+    /// This happens when `stdin` is closed (for example with Ctrl-D) or a
+    /// piped input is exhausted. An empty line is *not* end-of-file.
+    Eof,
+}
+
+impl ReadError {
+    /// Build a `Parse` error from the offending text and its `FromStr` error.
+    pub(crate) fn parse<E>(input: &str, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        ReadError::Parse {
+            input: input.to_string(),
+            source: source.into(),
+        }
+    }
+
+    /// Wrap an error with the position of the value it belongs to.
+    pub(crate) fn at(position: Position, source: ReadError) -> Self {
+        ReadError::At {
+            position,
+            source: Box::new(source),
+        }
+    }
+
+    /// Whether the error is caused by the entered text, so asking again may
+    /// succeed.
+    pub(crate) fn is_retryable(&self) -> bool {
+        match self {
+            ReadError::Parse { .. } | ReadError::Invalid(_) | ReadError::Mismatch { .. } => true,
+            ReadError::At { source, .. } => source.is_retryable(),
+            ReadError::Io(_) | ReadError::Interrupted | ReadError::Eof => false,
+        }
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "failed to read input: {}", err),
+            ReadError::Parse { input, source } => write!(f, "'{}' is not valid: {}", input, source),
+            ReadError::Invalid(message) => write!(f, "{}", message),
+            ReadError::At { position, source } => write!(f, "{}: {}", position, source),
+            ReadError::Mismatch {
+                column,
+                expected,
+                found,
+            } => {
+                if found.is_empty() {
+                    write!(
+                        f,
+                        "column {}: expected {}, found end of line",
+                        column, expected
+                    )
+                } else {
+                    write!(
+                        f,
+                        "column {}: expected {}, found '{}'",
+                        column, expected, found
+                    )
+                }
+            }
+            ReadError::Interrupted => write!(f, "input interrupted"),
+            ReadError::Eof => write!(f, "unexpected end of input"),
+        }
+    }
+}
+
+impl Error for ReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(err) => Some(err),
+            ReadError::Parse { source, .. } => Some(source.as_ref()),
+            ReadError::At { source, .. } => Some(source.as_ref()),
+            ReadError::Invalid(_)
+            | ReadError::Mismatch { .. }
+            | ReadError::Interrupted
+            | ReadError::Eof => None,
+        }
+    }
+}
+
+/// Where a value is within a larger input, as reported by `ReadError::At`.
+///
+/// All positions are 1-based, matching how they are shown to users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    /// The n-th item of a delimited list.
+    Item(usize),
+    /// The n-th field of a tuple.
+    Field(usize),
+    /// The n-th line of a multi-line block.
+    Line(usize),
+    /// The n-th row of a matrix or grid.
+    Row(usize),
+    /// A single cell of a matrix.
+    Cell {
+        /// The cell's row.
+        row: usize,
+        /// The cell's column.
+        col: usize,
+    },
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Position::Item(n) => write!(f, "item {}", n),
+            Position::Field(n) => write!(f, "field {}", n),
+            Position::Line(n) => write!(f, "line {}", n),
+            Position::Row(n) => write!(f, "row {}", n),
+            Position::Cell { row, col } => write!(f, "row {}, column {}", row, col),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
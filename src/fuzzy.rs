@@ -0,0 +1,158 @@
+/// The result of a successful [`fuzzy_match`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// How well the pattern matches; higher is better.
+    ///
+    /// Scores are only meaningful relative to other matches of the same
+    /// pattern.
+    pub score: i64,
+    /// The character indices in the candidate that matched the pattern, in
+    /// increasing order.
+    pub positions: Vec<usize>,
+}
+
+const MATCH: i64 = 16;
+const CONSECUTIVE: i64 = 8;
+const START: i64 = 10;
+const WORD_START: i64 = 8;
+const CAMEL_CASE: i64 = 6;
+const MAX_GAP_PENALTY: i64 = 8;
+
+/// Match `pattern` against `candidate` as a case-insensitive subsequence.
+///
+/// Every character of `pattern` must appear in `candidate` in order, but not
+/// necessarily next to each other. Matches score higher when the characters
+/// are consecutive, or at the start of the candidate or of a word within it
+/// (after a space, `-`, `_`, `.`, `/`, or at a camelCase hump). The best
+/// alignment is returned along with the matched positions, which can be used
+/// to highlight the match.
+///
+/// An empty pattern matches everything with a score of 0.
+///
+/// ## Example
+///
+/// ```
+/// use tinyinput::fuzzy_match;
+///
+/// let m = fuzzy_match("fb", "feature/bar").unwrap();
+/// assert_eq!(m.positions, [0, 8]);
+///
+/// assert!(fuzzy_match("xyz", "feature/bar").is_none());
+///
+/// let exact = fuzzy_match("main", "main").unwrap();
+/// let spread = fuzzy_match("main", "my-admin").unwrap();
+/// assert!(exact.score > spread.score);
+/// ```
+pub fn fuzzy_match(pattern: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().map(fold).collect();
+    let chars: Vec<char> = candidate.chars().collect();
+
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    let (m, n) = (pattern.len(), chars.len());
+    if m > n {
+        return None;
+    }
+
+    // best[i][j]: best score with pattern[i] matched at chars[j].
+    // from[i][j]: where pattern[i - 1] was matched in that alignment.
+    let mut best = vec![vec![None::<i64>; n]; m];
+    let mut from = vec![vec![0usize; n]; m];
+
+    for i in 0..m {
+        for j in i..n {
+            if fold(chars[j]) != pattern[i] {
+                continue;
+            }
+
+            let bonus = MATCH + bonus(&chars, j);
+
+            if i == 0 {
+                best[i][j] = Some(bonus - (j as i64).min(MAX_GAP_PENALTY));
+                continue;
+            }
+
+            let previous = (i - 1..j)
+                .filter_map(|k| {
+                    let score = best[i - 1][k]?;
+                    let link = if k + 1 == j {
+                        CONSECUTIVE
+                    } else {
+                        -((j - k - 1) as i64).min(MAX_GAP_PENALTY)
+                    };
+                    Some((score + link, k))
+                })
+                .max_by_key(|&(score, k)| (score, std::cmp::Reverse(k)));
+
+            if let Some((score, k)) = previous {
+                best[i][j] = Some(score + bonus);
+                from[i][j] = k;
+            }
+        }
+    }
+
+    let (score, mut j) = (0..n)
+        .filter_map(|j| Some((best[m - 1][j]?, j)))
+        .max_by_key(|&(score, j)| (score, std::cmp::Reverse(j)))?;
+
+    let mut positions = vec![0; m];
+    for i in (0..m).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+
+    Some(FuzzyMatch { score, positions })
+}
+
+/// Rank `candidates` by how well they match `pattern`, best first.
+///
+/// Returns the indices of the matching candidates with their matches.
+/// Candidates with equal scores keep their original order.
+///
+/// ## Example
+///
+/// ```
+/// let hosts = ["db-primary", "web-1", "db-replica"];
+/// let ranked = tinyinput::fuzzy_rank("dbr", &hosts);
+///
+/// assert_eq!(ranked[0].0, 2);
+/// ```
+pub fn fuzzy_rank<S>(pattern: &str, candidates: &[S]) -> Vec<(usize, FuzzyMatch)>
+where
+    S: AsRef<str>,
+{
+    let mut ranked: Vec<(usize, FuzzyMatch)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| Some((index, fuzzy_match(pattern, candidate.as_ref())?)))
+        .collect();
+
+    ranked.sort_by_key(|(index, m)| (std::cmp::Reverse(m.score), *index));
+    ranked
+}
+
+/// Extra score for a match at `chars[j]` based on where it is in a word.
+fn bonus(chars: &[char], j: usize) -> i64 {
+    if j == 0 {
+        return START;
+    }
+
+    let (prev, cur) = (chars[j - 1], chars[j]);
+    if matches!(prev, ' ' | '-' | '_' | '.' | '/' | '\\' | ':') {
+        WORD_START
+    } else if prev.is_lowercase() && cur.is_uppercase() {
+        CAMEL_CASE
+    } else {
+        0
+    }
+}
+
+/// Fold a character for case-insensitive comparison.
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
@@ -0,0 +1,83 @@
+/// Suggests text to show after the cursor while typing, like the fish shell.
+///
+/// While reading from a terminal, the hinter is asked for a hint after every
+/// key when the cursor is at the end of the line. The hint is shown dimmed
+/// after the cursor (without styling if `NO_COLOR` is set), and Right or End
+/// inserts it into the line.
+///
+/// Set a hinter on a prompt with [`Prompt::hinter`](crate::Prompt::hinter).
+/// Closures taking `(&str, &[&str])` and returning `Option<String>`
+/// implement this trait.
+///
+/// ## Example
+///
+/// ```
+/// use tinyinput::Hinter;
+///
+/// /// Suggests a fixed domain after an `@`.
+/// struct Domain;
+///
+/// impl Hinter for Domain {
+///     fn hint(&self, line: &str, _history: &[&str]) -> Option<String> {
+///         line.ends_with('@').then(|| "example.com".to_string())
+///     }
+/// }
+///
+/// assert_eq!(Domain.hint("ann@", &[]).as_deref(), Some("example.com"));
+/// ```
+pub trait Hinter {
+    /// Return the text to show after `line`, if any.
+    ///
+    /// `history` holds the earlier lines of the prompt's history namespace,
+    /// oldest first.
+    fn hint(&self, line: &str, history: &[&str]) -> Option<String>;
+}
+
+impl<F> Hinter for F
+where
+    F: Fn(&str, &[&str]) -> Option<String>,
+{
+    fn hint(&self, line: &str, history: &[&str]) -> Option<String> {
+        self(line, history)
+    }
+}
+
+/// Suggests the rest of the most recent history entry that starts with the
+/// typed line.
+///
+/// ## Example
+///
+/// ```
+/// use tinyinput::{Hinter, HistoryHinter};
+///
+/// let history = ["git status", "git commit", "ls"];
+///
+/// assert_eq!(HistoryHinter::new().hint("git s", &history).as_deref(), Some("tatus"));
+/// assert_eq!(HistoryHinter::new().hint("git", &history).as_deref(), Some(" commit"));
+/// assert_eq!(HistoryHinter::new().hint("", &history), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HistoryHinter {
+    _private: (),
+}
+
+impl HistoryHinter {
+    /// Create a history hinter.
+    pub fn new() -> Self {
+        HistoryHinter { _private: () }
+    }
+}
+
+impl Hinter for HistoryHinter {
+    fn hint(&self, line: &str, history: &[&str]) -> Option<String> {
+        if line.is_empty() {
+            return None;
+        }
+
+        history
+            .iter()
+            .rev()
+            .find_map(|entry| entry.strip_prefix(line).filter(|rest| !rest.is_empty()))
+            .map(str::to_string)
+    }
+}
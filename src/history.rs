@@ -0,0 +1,467 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The namespace used by prompts that do not choose one.
+pub(crate) const DEFAULT_NAMESPACE: &str = "";
+
+/// The default maximum number of entries kept.
+const MAX_SIZE: usize = 1000;
+
+/// A list of previously entered lines, optionally kept in a file.
+///
+/// Attach a history to an [`Input`](crate::Input) with
+/// [`Input::with_history`](crate::Input::with_history) to let the line
+/// editor recall earlier lines with Up and Down. Lines are recorded when
+/// they are read from a terminal by single-value reads such as
+/// [`Input::read`](crate::Input::read) and [`Prompt`](crate::Prompt).
+/// [`Secret`](crate::Secret) and [`Confirm`](crate::Confirm) answers, menu
+/// choices, and the lines of multi-line reads such as
+/// [`Input::read_block`](crate::Input::read_block) are never recorded.
+///
+/// Entries are grouped into namespaces so that unrelated prompts do not
+/// share history. Prompts use the default namespace (`""`) unless they
+/// choose one with [`Prompt::history`](crate::Prompt::history).
+///
+/// ## Example
+///
+/// ```
+/// use tinyinput::History;
+///
+/// let mut history = History::new().max_size(4);
+///
+/// for entry in ["ls", "cd src", "ls", " secret", "make", "make test"] {
+///     history.add("", entry).unwrap();
+/// }
+/// history.add("hosts", "example.com").unwrap();
+///
+/// // Duplicates are moved to the end, entries starting with a space are
+/// // skipped, and only the newest four entries are kept in total.
+/// assert_eq!(history.entries(""), ["ls", "make", "make test"]);
+/// assert_eq!(history.entries("hosts"), ["example.com"]);
+/// ```
+///
+/// ## Files
+///
+/// [`History::open`] loads entries from a file and appends every new entry
+/// to it, so several programs (or several runs of one program) can share a
+/// history. The file is rewritten without old and duplicate entries once it
+/// grows to twice the maximum size; it is read again first, so entries added
+/// by other programs in the meantime are kept.
+///
+/// ```no_run
+/// use tinyinput::{History, Input};
+///
+/// let history = History::open(".myapp_history").unwrap().max_size(500);
+/// let mut input = Input::stdin().with_history(history);
+///
+/// let command: String = input.read("> ").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct History {
+    /// `(namespace, entry)` pairs, oldest first.
+    entries: Vec<(String, String)>,
+    path: Option<PathBuf>,
+    /// Number of lines in the file, including dropped entries.
+    file_lines: usize,
+    max_size: usize,
+    dedup: bool,
+    ignore_space: bool,
+    /// The error from the last file write, if it failed.
+    last_error: Option<Arc<io::Error>>,
+}
+
+impl History {
+    /// Create an empty history that is only kept in memory.
+    ///
+    /// At most 1000 entries are kept, duplicates are removed and entries
+    /// starting with a space are ignored.
+    pub fn new() -> Self {
+        History {
+            entries: Vec::new(),
+            path: None,
+            file_lines: 0,
+            max_size: MAX_SIZE,
+            dedup: true,
+            ignore_space: true,
+            last_error: None,
+        }
+    }
+
+    /// Load a history from `path` and append new entries to it.
+    ///
+    /// Every entry in the file is loaded, even beyond the default maximum
+    /// size, so a larger limit can still be set with
+    /// [`max_size`](History::max_size) afterwards. The limit is applied when
+    /// it is set and when entries are added. A missing file is created when
+    /// the first entry is added.
+    ///
+    /// ```
+    /// use tinyinput::History;
+    ///
+    /// let path = std::env::temp_dir().join("tinyinput-doc-open-history");
+    /// let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut history = History::open(&path).unwrap().max_size(2000);
+    /// for n in 0..1500 {
+    ///     history.add("", &n.to_string()).unwrap();
+    /// }
+    ///
+    /// let history = History::open(&path).unwrap().max_size(2000);
+    /// assert_eq!(history.len(), 1500);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the file exists but cannot be read.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut history = History::new();
+        history.path = Some(path.as_ref().to_path_buf());
+        history.load()?;
+
+        Ok(history)
+    }
+
+    /// Replace the entries with the ones in the file, without trimming.
+    fn load(&mut self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+
+        self.entries.clear();
+        self.file_lines = 0;
+
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            let (namespace, entry) = match line.split_once('\t') {
+                Some((namespace, entry)) => (unescape(namespace), unescape(entry)),
+                None => (DEFAULT_NAMESPACE.to_string(), unescape(&line)),
+            };
+
+            self.insert(namespace, entry);
+            self.file_lines += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Keep at most `max_size` entries in all namespaces together, dropping
+    /// the oldest ones.
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self.trim();
+        self
+    }
+
+    /// Whether adding an entry removes earlier copies of it in the same
+    /// namespace. Enabled by default.
+    pub fn dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Whether entries starting with a space are ignored, like
+    /// `HISTCONTROL=ignorespace` in bash. Enabled by default.
+    pub fn ignore_space(mut self, ignore_space: bool) -> Self {
+        self.ignore_space = ignore_space;
+        self
+    }
+
+    /// Add `entry` to `namespace`, and append it to the file if there is one.
+    ///
+    /// Returns `Ok(false)` if the entry was ignored because it is empty, it
+    /// starts with a space, or it repeats the previous entry.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the file cannot be written. The entry is still
+    /// added to the in-memory history, and the error is also kept for
+    /// [`last_error`](History::last_error).
+    pub fn add(&mut self, namespace: &str, entry: &str) -> io::Result<bool> {
+        let ignored = entry.trim().is_empty()
+            || (self.ignore_space && entry.starts_with(' '))
+            || self.entries(namespace).last() == Some(&entry);
+
+        if ignored {
+            return Ok(false);
+        }
+
+        self.push(namespace.to_string(), entry.to_string());
+        let result = self.append(namespace, entry);
+        self.record(result)?;
+        Ok(true)
+    }
+
+    /// The entries in `namespace`, oldest first.
+    pub fn entries(&self, namespace: &str) -> Vec<&str> {
+        self.entries
+            .iter()
+            .filter(|(name, _)| name == namespace)
+            .map(|(_, entry)| entry.as_str())
+            .collect()
+    }
+
+    /// The namespaces that have entries, in order of first use.
+    pub fn namespaces(&self) -> Vec<&str> {
+        let mut namespaces: Vec<&str> = Vec::new();
+
+        for (name, _) in &self.entries {
+            if !namespaces.contains(&name.as_str()) {
+                namespaces.push(name);
+            }
+        }
+
+        namespaces
+    }
+
+    /// The total number of entries in all namespaces.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether there are no entries in any namespace.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The file the history is kept in, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Remove all entries, and empty the file if there is one.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.entries.clear();
+        let result = self.save();
+        self.record(result)
+    }
+
+    /// The error from the last attempt to write the file, if it failed.
+    ///
+    /// Lines read by an [`Input`](crate::Input) are still returned when
+    /// they cannot be saved, so check this to report a history file that is
+    /// not being written. It is cleared by the next successful write.
+    ///
+    /// ```
+    /// use tinyinput::History;
+    ///
+    /// let path = std::env::temp_dir().join("tinyinput-no-such-dir/history");
+    /// let mut history = History::open(path).unwrap();
+    ///
+    /// assert!(history.add("", "ls").is_err());
+    /// assert_eq!(history.entries(""), ["ls"]);
+    /// assert!(history.last_error().is_some());
+    /// ```
+    pub fn last_error(&self) -> Option<&io::Error> {
+        self.last_error.as_deref()
+    }
+
+    /// Remember the outcome of a file write for [`last_error`](History::last_error).
+    fn record(&mut self, result: io::Result<()>) -> io::Result<()> {
+        match result {
+            Ok(()) => {
+                self.last_error = None;
+                Ok(())
+            }
+            Err(err) => {
+                let kind = err.kind();
+                let err = Arc::new(err);
+                self.last_error = Some(Arc::clone(&err));
+                Err(io::Error::new(kind, err))
+            }
+        }
+    }
+
+    /// Add an entry in memory only.
+    fn push(&mut self, namespace: String, entry: String) {
+        self.insert(namespace, entry);
+        self.trim();
+    }
+
+    /// Add an entry in memory only, without trimming.
+    fn insert(&mut self, namespace: String, entry: String) {
+        if self.dedup {
+            self.entries
+                .retain(|(name, old)| name != &namespace || old != &entry);
+        }
+
+        self.entries.push((namespace, entry));
+    }
+
+    fn trim(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.max_size);
+        self.entries.drain(..excess);
+    }
+
+    /// Append a new entry to the file, rewriting it if it grew too large.
+    fn append(&mut self, namespace: &str, entry: &str) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}\t{}", escape(namespace), escape(entry))?;
+        self.file_lines += 1;
+
+        if self.file_lines >= self.max_size.saturating_mul(2) {
+            self.compact()?;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrite the file without old and duplicate entries.
+    ///
+    /// The file is read again first, so entries appended by other programs
+    /// since it was opened are kept, and are then also in memory.
+    fn compact(&mut self) -> io::Result<()> {
+        self.load()?;
+        self.trim();
+        self.save()
+    }
+
+    /// Replace the file with the current entries.
+    fn save(&mut self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let mut temp = path.clone().into_os_string();
+        temp.push(".tmp");
+
+        let mut writer = BufWriter::new(File::create(&temp)?);
+        for (namespace, entry) in &self.entries {
+            writeln!(writer, "{}\t{}", escape(namespace), escape(entry))?;
+        }
+        writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?;
+
+        fs::rename(&temp, path)?;
+        self.file_lines = self.entries.len();
+        Ok(())
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        History::new()
+    }
+}
+
+/// Escape the characters that separate fields and entries in the file.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A path in the temporary directory that does not exist yet.
+    fn temp_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("tinyinput-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn compaction_keeps_entries_from_other_handles() {
+        let path = temp_path("shared-history");
+        let mut first = History::open(&path).unwrap().max_size(3);
+        let mut second = History::open(&path).unwrap().max_size(3);
+
+        for n in 0..5 {
+            first.add("", &format!("first {}", n)).unwrap();
+        }
+        second.add("", "second 0").unwrap();
+
+        // This reaches twice the maximum size and rewrites the file.
+        first.add("", "first 5").unwrap();
+        assert_eq!(first.entries(""), ["first 4", "second 0", "first 5"]);
+
+        // Both handles keep appending, and each rewrites the file in turn.
+        for n in 6..20 {
+            first.add("", &format!("first {}", n)).unwrap();
+            second.add("", &format!("second {}", n)).unwrap();
+        }
+
+        let history = History::open(&path).unwrap().max_size(3);
+        assert_eq!(history.entries(""), ["second 18", "first 19", "second 19"]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn duplicates_and_leading_spaces_can_be_kept() {
+        let mut history = History::new().dedup(false).ignore_space(false);
+
+        for entry in ["ls", "make", "ls", " secret"] {
+            assert!(history.add("", entry).unwrap());
+        }
+        // Repeating the previous entry is still ignored.
+        assert!(!history.add("", " secret").unwrap());
+        assert!(!history.add("", "  ").unwrap());
+
+        assert_eq!(history.entries(""), ["ls", "make", "ls", " secret"]);
+    }
+
+    #[test]
+    fn dedup_is_per_namespace() {
+        let mut history = History::new();
+
+        for (namespace, entry) in [("", "ls"), ("hosts", "ls"), ("", "make"), ("", "ls")] {
+            history.add(namespace, entry).unwrap();
+        }
+
+        assert_eq!(history.entries(""), ["make", "ls"]);
+        assert_eq!(history.entries("hosts"), ["ls"]);
+        assert_eq!(history.namespaces(), ["hosts", ""]);
+    }
+}
@@ -0,0 +1,610 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{self, BufRead, StdinLock, Stdout, Write};
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::editor::{Editor, LineOptions};
+use crate::list::parse_items;
+use crate::matrix::{grid_row, parse_row};
+use crate::pattern::captures;
+use crate::prompt::with_hint;
+use crate::term::{self, RawMode};
+use crate::tuple::split_fields;
+use crate::{Delimiter, FromFields, History, Position, Prompt, ReadError, Terminator};
+
+/// The history shared by handles from [`Input::stdin`], so lines typed at
+/// one free-function call can be recalled at the next.
+static SESSION_HISTORY: Mutex<Option<History>> = Mutex::new(None);
+
+/// A reusable input handle over any reader and prompt writer.
+///
+/// `Input` owns a buffered reader that lines are read from and a writer that
+/// prompts are printed to. This allows the same parsing behavior to be used
+/// with files, pipes, sockets, or in-memory buffers, not just the process's
+/// standard input and output.
+///
+/// ## Example
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::Input;
+///
+/// let mut input = Input::new(Cursor::new("42\nhello\n"), Vec::new());
+///
+/// let n: i32 = input.read("Number: ").unwrap();
+/// let s: String = input.read("Text: ").unwrap();
+///
+/// assert_eq!(n, 42);
+/// assert_eq!(s, "hello");
+/// assert_eq!(input.writer(), b"Number: Text: ");
+/// ```
+///
+/// Reading past the end of the input returns `ReadError::Eof`, which makes
+/// "read until the input is closed" loops straightforward:
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Input, ReadError};
+///
+/// let mut input = Input::new(Cursor::new("1\n2\n\n"), std::io::sink());
+/// let mut lines = Vec::new();
+///
+/// loop {
+///     match input.read::<String>("> ") {
+///         Ok(line) => lines.push(line),
+///         Err(ReadError::Eof) => break,
+///         Err(err) => panic!("{err}"),
+///     }
+/// }
+///
+/// assert_eq!(lines, ["1", "2", ""]);
+/// ```
+///
+/// ## Line editing
+///
+/// When a handle from [`Input::stdin`] reads from and prompts on a terminal,
+/// every line is read with a built-in editor using the usual Emacs-style
+/// keys:
+///
+/// | Keys | Action |
+/// |------|--------|
+/// | Left / Right, Ctrl-B / Ctrl-F | Move by one character |
+/// | Ctrl-Left / Ctrl-Right, Alt-B / Alt-F | Move by one word |
+/// | Home / End, Ctrl-A / Ctrl-E | Move to the start or end of the line |
+/// | Backspace, Delete / Ctrl-D | Delete the character before or under the cursor |
+/// | Ctrl-K / Ctrl-U | Cut to the end or start of the line |
+/// | Ctrl-W, Alt-Backspace / Alt-D | Cut the previous or next word |
+/// | Ctrl-Y | Paste the last cut text |
+/// | Up / Down, Ctrl-P / Ctrl-N | Browse earlier lines |
+/// | Ctrl-R | Search earlier lines (see below) |
+/// | Tab | Complete the line, if the prompt has a [`Completer`](crate::Completer) |
+/// | Right / End at the end of the line | Accept the hint, if the prompt has a [`Hinter`](crate::Hinter) |
+/// | Ctrl-L | Clear the screen |
+/// | Ctrl-C | Return `ReadError::Interrupted` |
+/// | Ctrl-D on an empty line | Return `ReadError::Eof` |
+/// | Ctrl-Z / Ctrl-\ | Suspend or quit the program, as in the shell |
+///
+/// Ctrl-R starts a reverse incremental search, shown as
+/// ``(reverse-i-search)`query': match``. Typing narrows the search, Ctrl-R
+/// again steps to older matches, Enter accepts the match, Escape or a
+/// movement key keeps it for editing, and Ctrl-G restores the original line.
+///
+/// Lines read on a terminal are added to the handle's [`History`]. Handles
+/// from [`Input::stdin`], including the ones that free functions such as
+/// [`read`](crate::read) create for every call, share an in-memory history
+/// for the rest of the process, so Up recalls lines typed at earlier calls.
+/// Attach a file-backed history with [`with_history`](Input::with_history)
+/// to keep lines between runs; that handle then uses only its own history.
+#[derive(Debug)]
+pub struct Input<R, W> {
+    reader: R,
+    writer: W,
+    terminal: bool,
+    interactive: bool,
+    history: History,
+    /// Whether lines are also added to `SESSION_HISTORY`.
+    session: bool,
+}
+
+impl Input<StdinLock<'static>, Stdout> {
+    /// Create an input handle reading from `stdin` and prompting on `stdout`.
+    ///
+    /// The handle holds the `stdin` lock for as long as it is alive.
+    ///
+    /// If `stdin` and `stdout` are terminals, lines are read with a built-in
+    /// line editor (see [`Input`]) that recalls lines from the process's
+    /// session history. If `stdin` is a terminal, reads that need terminal
+    /// control (such as [`Secret`](crate::Secret)) use it. Handles created
+    /// with [`new`](Input::new) always read plain lines.
+    pub fn stdin() -> Self {
+        let history = session_history().get_or_insert_with(History::new).clone();
+
+        Input {
+            terminal: term::is_terminal(),
+            interactive: term::is_interactive(),
+            history,
+            session: true,
+            ..Input::new(io::stdin().lock(), io::stdout())
+        }
+    }
+}
+
+impl<R, W> Input<R, W>
+where
+    R: BufRead,
+    W: Write,
+{
+    /// Create an input handle from a reader and a prompt writer.
+    pub fn new(reader: R, writer: W) -> Self {
+        Input {
+            reader,
+            writer,
+            terminal: false,
+            interactive: false,
+            history: History::new(),
+            session: false,
+        }
+    }
+
+    /// Read a line of input and parse it into type `T`.
+    ///
+    /// This behaves exactly like [`read`](crate::read), but uses this handle's
+    /// reader and writer instead of `stdin` and `stdout`.
+    ///
+    /// ## Errors
+    ///
+    /// - Returns `ReadError::Io` if writing the prompt or reading the line fails.
+    /// - Returns `ReadError::Eof` if the reader is exhausted.
+    /// - Returns `ReadError::Parse` with the trimmed input and the `FromStr`
+    ///   error if parsing into `T` fails.
+    pub fn read<T>(&mut self, prompt: &str) -> Result<T, ReadError>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let line = self.read_line_with(prompt, LineOptions::default())?;
+
+        parse(line.trim())
+    }
+
+    /// Read a line of input as an optional value.
+    ///
+    /// An empty line (after trimming) yields `Ok(None)`. Non-empty input is
+    /// parsed as with [`read`](Input::read), so invalid input is still
+    /// reported as `ReadError::Parse`.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::Input;
+    ///
+    /// let mut input = Input::new(Cursor::new("\n42\n"), std::io::sink());
+    ///
+    /// assert_eq!(input.read_opt::<u32>("Age (optional): ").unwrap(), None);
+    /// assert_eq!(input.read_opt::<u32>("Age (optional): ").unwrap(), Some(42));
+    /// ```
+    pub fn read_opt<T>(&mut self, prompt: &str) -> Result<Option<T>, ReadError>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        Prompt::new(prompt).read_opt_from(self)
+    }
+
+    /// Read a line of input and parse it into a `Vec<T>`.
+    ///
+    /// This behaves like [`read_vec`](crate::read_vec), but uses this
+    /// handle's reader and writer.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::{Delimiter, Input, Position, ReadError};
+    ///
+    /// let mut input = Input::new(Cursor::new("1; 2; 3\n4 x 6\n"), std::io::sink());
+    ///
+    /// let values: Vec<i32> = input.read_vec("Values: ", Delimiter::semicolon()).unwrap();
+    /// assert_eq!(values, [1, 2, 3]);
+    ///
+    /// let err = input.read_vec::<i32>("Values: ", Delimiter::whitespace()).unwrap_err();
+    /// assert!(matches!(err, ReadError::At { position: Position::Item(2), .. }));
+    /// assert_eq!(err.to_string(), "item 2: 'x' is not valid: invalid digit found in string");
+    /// ```
+    pub fn read_vec<T>(&mut self, prompt: &str, delimiter: Delimiter) -> Result<Vec<T>, ReadError>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let line = self.read_line_with(prompt, LineOptions::default())?;
+
+        parse_items(line.trim(), &delimiter)
+    }
+
+    /// Read a line of input and parse it into a tuple.
+    ///
+    /// This behaves like [`read_tuple`](crate::read_tuple), but uses this
+    /// handle's reader and writer.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::{Input, Position, ReadError};
+    ///
+    /// let mut input = Input::new(Cursor::new("Ann 42\n(1.5, -2)\nBob old\n"), std::io::sink());
+    ///
+    /// let (name, age): (String, u32) = input.read_tuple("Name and age: ").unwrap();
+    /// assert_eq!((name.as_str(), age), ("Ann", 42));
+    ///
+    /// let (x, y): (f64, f64) = input.read_tuple("Point: ").unwrap();
+    /// assert_eq!((x, y), (1.5, -2.0));
+    ///
+    /// let err = input.read_tuple::<(String, u32)>("Name and age: ").unwrap_err();
+    /// assert!(matches!(err, ReadError::At { position: Position::Field(2), .. }));
+    /// ```
+    pub fn read_tuple<T>(&mut self, prompt: &str) -> Result<T, ReadError>
+    where
+        T: FromFields,
+    {
+        let line = self.read_line_with(prompt, LineOptions::default())?;
+
+        T::from_fields(&split_fields(line.trim()))
+    }
+
+    /// Read a line of input and extract values using a pattern.
+    ///
+    /// This behaves like [`scan`](crate::scan), but uses this handle's
+    /// reader and writer.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::{Input, ReadError};
+    ///
+    /// let mut input = Input::new(
+    ///     Cursor::new("move 3 from 1 to 2\n2024-05-01\nmove 3 form 1 to 2\n"),
+    ///     std::io::sink(),
+    /// );
+    ///
+    /// let (n, from, to): (u32, u8, u8) = input.scan("", "move {} from {} to {}").unwrap();
+    /// assert_eq!((n, from, to), (3, 1, 2));
+    ///
+    /// let (year, month, day): (u16, u8, u8) = input.scan("Date: ", "{}-{}-{}").unwrap();
+    /// assert_eq!((year, month, day), (2024, 5, 1));
+    ///
+    /// let err = input.scan::<(u32, u8, u8)>("", "move {} from {} to {}").unwrap_err();
+    /// assert!(matches!(err, ReadError::Mismatch { column: 8, .. }));
+    /// assert_eq!(err.to_string(), "column 8: expected 'from', found 'form 1 to 2'");
+    /// ```
+    pub fn scan<T>(&mut self, prompt: &str, pattern: &str) -> Result<T, ReadError>
+    where
+        T: FromFields,
+    {
+        let line = self.read_line_with(prompt, LineOptions::default())?;
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        T::from_fields(&captures(pattern, line)?)
+    }
+
+    /// Read several lines of input as a single `String`.
+    ///
+    /// This behaves like [`read_block`](crate::read_block), but uses this
+    /// handle's reader and writer.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::{Input, Terminator};
+    ///
+    /// let mut input = Input::new(Cursor::new("first\n  second\nEND\nafter\n"), std::io::sink());
+    /// let text = input.read_block("Text:\n", Terminator::Marker("END".into())).unwrap();
+    ///
+    /// assert_eq!(text, "first\n  second");
+    /// assert_eq!(input.read::<String>("").unwrap(), "after");
+    /// ```
+    pub fn read_block(
+        &mut self,
+        prompt: &str,
+        terminator: Terminator,
+    ) -> Result<String, ReadError> {
+        Ok(self.read_block_lines(prompt, &terminator)?.join("\n"))
+    }
+
+    /// Read several lines of input, parsing each into `T`.
+    ///
+    /// This behaves like [`read_lines`](crate::read_lines), but uses this
+    /// handle's reader and writer.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::{Input, Position, ReadError, Terminator};
+    ///
+    /// let mut input = Input::new(Cursor::new("1\n2\n\n3\nx\n"), std::io::sink());
+    ///
+    /// let first: Vec<u32> = input.read_lines("", Terminator::Blank).unwrap();
+    /// assert_eq!(first, [1, 2]);
+    ///
+    /// let err = input.read_lines::<u32>("", Terminator::Eof).unwrap_err();
+    /// assert!(matches!(err, ReadError::At { position: Position::Line(2), .. }));
+    /// ```
+    pub fn read_lines<T>(
+        &mut self,
+        prompt: &str,
+        terminator: Terminator,
+    ) -> Result<Vec<T>, ReadError>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.read_block_lines(prompt, &terminator)?
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                parse(line.trim()).map_err(|err| ReadError::at(Position::Line(index + 1), err))
+            })
+            .collect()
+    }
+
+    /// Collect lines, without line endings, until `terminator` or end-of-input.
+    fn read_block_lines(
+        &mut self,
+        prompt: &str,
+        terminator: &Terminator,
+    ) -> Result<Vec<String>, ReadError> {
+        let mut lines = Vec::new();
+        let mut prompt = prompt;
+        let mut started = false;
+
+        loop {
+            let line = match self.read_line(prompt) {
+                Ok(line) => line,
+                Err(ReadError::Eof) if started => break,
+                Err(err) => return Err(err),
+            };
+            started = true;
+            let line = line.trim_end_matches(['\r', '\n']);
+
+            if terminator.matches(line) {
+                break;
+            }
+            lines.push(line.to_string());
+            prompt = "";
+        }
+
+        Ok(lines)
+    }
+
+    /// Read a `rows` x `cols` matrix.
+    ///
+    /// This behaves like [`read_matrix`](crate::read_matrix), but uses this
+    /// handle's reader and writer.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::{Input, Position, ReadError};
+    ///
+    /// let mut input = Input::new(Cursor::new("1 2 3\n4 5 6\n1 2\n3 x\n"), std::io::sink());
+    ///
+    /// let m: Vec<Vec<i32>> = input.read_matrix("", 2, 3).unwrap();
+    /// assert_eq!(m, [[1, 2, 3], [4, 5, 6]]);
+    ///
+    /// let err = input.read_matrix::<i32>("", 2, 2).unwrap_err();
+    /// assert!(matches!(err, ReadError::At { position: Position::Cell { row: 2, col: 2 }, .. }));
+    /// assert_eq!(err.to_string(), "row 2, column 2: 'x' is not valid: invalid digit found in string");
+    /// ```
+    pub fn read_matrix<T>(
+        &mut self,
+        prompt: &str,
+        rows: usize,
+        cols: usize,
+    ) -> Result<Vec<Vec<T>>, ReadError>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let mut matrix = Vec::with_capacity(rows);
+
+        for row in 1..=rows {
+            let line = self.read_line(if row == 1 { prompt } else { "" })?;
+            matrix.push(parse_row(&line, row, cols)?);
+        }
+
+        Ok(matrix)
+    }
+
+    /// Read a `rows` x `cols` grid of characters.
+    ///
+    /// This behaves like [`read_grid`](crate::read_grid), but uses this
+    /// handle's reader and writer.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::{Input, Position, ReadError};
+    ///
+    /// let mut input = Input::new(Cursor::new("#.#\n. .\n##\n"), std::io::sink());
+    ///
+    /// let err = input.read_grid("", 3, 3).unwrap_err();
+    /// assert!(matches!(err, ReadError::At { position: Position::Row(3), .. }));
+    /// assert_eq!(err.to_string(), "row 3: expected 3 characters, found 2");
+    /// ```
+    pub fn read_grid(
+        &mut self,
+        prompt: &str,
+        rows: usize,
+        cols: usize,
+    ) -> Result<Vec<Vec<char>>, ReadError> {
+        let mut grid = Vec::with_capacity(rows);
+
+        for row in 1..=rows {
+            let line = self.read_line(if row == 1 { prompt } else { "" })?;
+            grid.push(grid_row(line.trim_end_matches(['\r', '\n']), row, cols)?);
+        }
+
+        Ok(grid)
+    }
+
+    /// Read a line of input, using `default` if the line is empty.
+    ///
+    /// The default is shown in the prompt, so `"Port: "` is printed as
+    /// `"Port [8080]: "`. Non-empty input is parsed as with
+    /// [`read`](Input::read), so invalid input is still reported. This is
+    /// like [`Prompt::default`](crate::Prompt::default), but `default` is
+    /// returned as is, without needing to be `Clone`.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::Input;
+    ///
+    /// let mut input = Input::new(Cursor::new("\n9000\nabc\n"), std::io::sink());
+    ///
+    /// assert_eq!(input.read_or("Port: ", 8080).unwrap(), 8080);
+    /// assert_eq!(input.read_or("Port: ", 8080).unwrap(), 9000);
+    /// assert!(input.read_or("Port: ", 8080).is_err());
+    /// ```
+    pub fn read_or<T>(&mut self, prompt: &str, default: T) -> Result<T, ReadError>
+    where
+        T: FromStr + Display,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let text = with_hint(prompt, &default.to_string());
+        let line = self.read_line_with(&text, LineOptions::default())?;
+
+        match line.trim() {
+            "" => Ok(default),
+            line => parse(line),
+        }
+    }
+
+    /// Use `history` for lines read from a terminal.
+    ///
+    /// This replaces the handle's current history, and lines read by this
+    /// handle are no longer added to the session history shared by
+    /// [`Input::stdin`] handles.
+    pub fn with_history(mut self, history: History) -> Self {
+        self.history = history;
+        self.session = false;
+        self
+    }
+
+    /// Get a reference to the handle's history.
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Get a mutable reference to the handle's history.
+    pub fn history_mut(&mut self) -> &mut History {
+        &mut self.history
+    }
+
+    /// Print `prompt` (if non-empty) and read a single raw line, keeping it
+    /// out of the history.
+    ///
+    /// Used for answers that are not worth recalling, such as confirmations
+    /// and the rows of multi-line input.
+    ///
+    /// Returns `ReadError::Eof` if no bytes could be read.
+    pub(crate) fn read_line(&mut self, prompt: &str) -> Result<String, ReadError> {
+        let options = LineOptions {
+            history: None,
+            ..LineOptions::default()
+        };
+        self.read_line_with(prompt, options)
+    }
+
+    /// Like [`read_line`](Input::read_line), but on a terminal the line
+    /// editor uses `options`, and the line is recorded in the history
+    /// namespace they name.
+    pub(crate) fn read_line_with(
+        &mut self,
+        prompt: &str,
+        options: LineOptions<'_>,
+    ) -> Result<String, ReadError> {
+        if self.interactive {
+            let entries = options
+                .history
+                .map_or(Vec::new(), |name| self.history.entries(name));
+            let raw = RawMode::enable().map_err(ReadError::Io)?;
+            let line = Editor::new(prompt, entries, options).read_line(
+                &mut self.reader,
+                &mut self.writer,
+                &raw,
+            );
+            drop(raw);
+            let line = line?;
+
+            // A history file that cannot be written must not lose the line;
+            // the error is kept in `History::last_error` instead.
+            if let Some(namespace) = options.history {
+                let entry = line.trim_end_matches(['\r', '\n']);
+                let _ = self.history.add(namespace, entry);
+
+                if self.session {
+                    let _ = session_history()
+                        .get_or_insert_with(History::new)
+                        .add(namespace, entry);
+                }
+            }
+            return Ok(line);
+        }
+
+        let mut temp = String::new();
+
+        if !prompt.is_empty() {
+            write!(self.writer, "{}", prompt).map_err(ReadError::Io)?;
+            self.writer.flush().map_err(ReadError::Io)?;
+        }
+
+        if self.reader.read_line(&mut temp).map_err(ReadError::Io)? == 0 {
+            return Err(ReadError::Eof);
+        }
+
+        Ok(temp)
+    }
+
+    /// Whether the handle reads from a terminal that supports raw mode.
+    pub(crate) fn is_terminal(&self) -> bool {
+        self.terminal
+    }
+
+    /// Whether the handle also prompts on a terminal, so interactive menus
+    /// and the line editor can be drawn.
+    pub(crate) fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+
+    /// Get a reference to the underlying reader.
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader.
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Get a reference to the underlying prompt writer.
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get a mutable reference to the underlying prompt writer.
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consume the handle, returning the reader and the prompt writer.
+    pub fn into_inner(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+}
+
+/// Lock the session history, which stays usable after a panic elsewhere.
+fn session_history() -> MutexGuard<'static, Option<History>> {
+    SESSION_HISTORY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Parse already-trimmed text into `T`, keeping the text and error on failure.
+pub(crate) fn parse<T>(text: &str) -> Result<T, ReadError>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    text.parse::<T>().map_err(|err| ReadError::parse(text, err))
+}
@@ -0,0 +1,127 @@
+use std::io::{self, BufRead};
+
+use crate::secret::read_byte;
+use crate::term::RawMode;
+
+/// A key press decoded from raw terminal input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Key {
+    Char(char),
+    /// Ctrl plus a letter, given in lowercase, or `'\\'` for Ctrl-Backslash.
+    Ctrl(char),
+    /// Alt (or Escape) plus a character.
+    Alt(char),
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Esc,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    CtrlLeft,
+    CtrlRight,
+    Unknown,
+}
+
+/// Read the next key press, or `None` at end-of-input.
+pub(crate) fn read_key<R: BufRead>(reader: &mut R, raw: &RawMode) -> io::Result<Option<Key>> {
+    let byte = match read_byte(reader)? {
+        Some(byte) => byte,
+        None => return Ok(None),
+    };
+
+    let key = match byte {
+        b'\r' | b'\n' => Key::Enter,
+        b'\t' => Key::Tab,
+        0x7f | 0x08 => Key::Backspace,
+        0x1b => read_escape(reader, raw)?,
+        0x01..=0x1a => Key::Ctrl(char::from(b'a' + byte - 1)),
+        0x1c => Key::Ctrl('\\'),
+        0x00..=0x1f => Key::Unknown,
+        byte => read_char(reader, byte)?.map_or(Key::Unknown, Key::Char),
+    };
+
+    Ok(Some(key))
+}
+
+/// Decode the rest of an escape sequence after `ESC`.
+fn read_escape<R: BufRead>(reader: &mut R, raw: &RawMode) -> io::Result<Key> {
+    // A lone Escape key is not followed by anything, so wait only briefly.
+    let byte = match raw.with_timeout(|| read_byte(reader))? {
+        Some(byte) => byte,
+        None => return Ok(Key::Esc),
+    };
+
+    match byte {
+        b'[' => read_csi(reader),
+        b'O' => Ok(match read_byte(reader)? {
+            Some(b'A') => Key::Up,
+            Some(b'B') => Key::Down,
+            Some(b'C') => Key::Right,
+            Some(b'D') => Key::Left,
+            Some(b'H') => Key::Home,
+            Some(b'F') => Key::End,
+            _ => Key::Unknown,
+        }),
+        0x1b => Ok(Key::Esc),
+        byte if byte < 0x80 => Ok(Key::Alt(char::from(byte))),
+        _ => Ok(Key::Unknown),
+    }
+}
+
+/// Decode a `ESC [` control sequence.
+fn read_csi<R: BufRead>(reader: &mut R) -> io::Result<Key> {
+    let mut params = String::new();
+
+    let last = loop {
+        match read_byte(reader)? {
+            Some(byte @ 0x40..=0x7e) => break byte,
+            Some(byte) => params.push(char::from(byte)),
+            None => return Ok(Key::Unknown),
+        }
+    };
+
+    Ok(match (params.as_str(), last) {
+        (_, b'A') => Key::Up,
+        (_, b'B') => Key::Down,
+        ("1;5" | "5", b'C') => Key::CtrlRight,
+        ("1;5" | "5", b'D') => Key::CtrlLeft,
+        (_, b'C') => Key::Right,
+        (_, b'D') => Key::Left,
+        (_, b'H') | ("1" | "7", b'~') => Key::Home,
+        (_, b'F') | ("4" | "8", b'~') => Key::End,
+        ("3", b'~') => Key::Delete,
+        ("5", b'~') => Key::PageUp,
+        ("6", b'~') => Key::PageDown,
+        _ => Key::Unknown,
+    })
+}
+
+/// Decode a UTF-8 character starting with `first`.
+fn read_char<R: BufRead>(reader: &mut R, first: u8) -> io::Result<Option<char>> {
+    let len = match first {
+        0x00..=0x7f => 1,
+        0xc0..=0xdf => 2,
+        0xe0..=0xef => 3,
+        0xf0..=0xf7 => 4,
+        _ => return Ok(None),
+    };
+
+    let mut bytes = vec![first];
+    for _ in 1..len {
+        match read_byte(reader)? {
+            Some(byte) => bytes.push(byte),
+            None => return Ok(None),
+        }
+    }
+
+    Ok(std::str::from_utf8(&bytes)
+        .ok()
+        .and_then(|text| text.chars().next()))
+}
@@ -26,7 +26,8 @@
 //!
 //! - ✔ Minimal and lightweight
 //! - ✔ No macros
-//! - ✔ No global state
+//! - ✔ No global configuration; the only shared state is the in-memory
+//!   line history of terminal sessions
 //! - ✔ No hidden panics
 //! - ✔ No dependencies
 //! - ✔ Suitable for small CLI tools, scripts, and learning Rust
@@ -40,18 +41,62 @@
 //! input from standard input and parsing it into a Rust type using `FromStr`.
 //! Error handling is explicit and returned to the caller, allowing each program
 //! to decide how to handle invalid input.
+//!
+//! ## Reading from other sources
+//!
+//! The free functions read from `stdin` and prompt on `stdout`. The same
+//! behavior is available for any [`BufRead`](std::io::BufRead) and
+//! [`Write`](std::io::Write) pair through [`Input`]:
+//!
+//! ```
+//! use std::io::Cursor;
+//!
+//! let mut input = tinyinput::Input::new(Cursor::new("7\n"), std::io::sink());
+//! let n: u8 = input.read("Enter count: ").unwrap();
+//! assert_eq!(n, 7);
+//! ```
 
-use std::io::{self, Write};
-use std::str::FromStr;
+mod block;
+mod complete;
+mod confirm;
+mod editor;
+mod error;
+mod fuzzy;
+mod hint;
+mod history;
+mod input;
+mod keys;
+mod list;
+mod matrix;
+mod menu;
+mod pattern;
+mod prompt;
+mod scanner;
+mod secret;
+mod select;
+mod term;
+mod tuple;
+mod unicode;
+pub mod validate;
 
-/// Errors that can occur while reading or parsing user input.
-#[derive(Debug)]
-pub enum ReadError {
-    /// An I/O error occurred while reading from standard input.
-    Io(io::Error),
-    /// The input could not be parsed into the requested type.
-    Parse,
-}
+pub use block::Terminator;
+pub use complete::{Candidate, Completer, FilenameCompleter, WordCompleter};
+pub use confirm::Confirm;
+pub use error::{Position, ReadError};
+pub use fuzzy::{fuzzy_match, fuzzy_rank, FuzzyMatch};
+pub use hint::{Hinter, HistoryHinter};
+pub use history::History;
+pub use input::Input;
+pub use list::Delimiter;
+pub use prompt::Prompt;
+pub use scanner::Scanner;
+pub use secret::{Secret, SecretString};
+pub use select::{MultiSelect, Select};
+pub use tuple::FromFields;
+
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
 
 /// Read a line of input from standard input and parse it into type `T`.
 ///
@@ -62,6 +107,9 @@ pub enum ReadError {
 /// The return type is inferred from the assignment context, which allows
 /// ergonomic usage without explicit type annotations at the call site.
 ///
+/// This is a thin wrapper around [`Input::read`] on an [`Input::stdin`]
+/// handle. Use [`Input`] directly to read from other sources.
+///
 /// ## Example
 ///
 /// ```no_run
@@ -78,19 +126,360 @@ pub enum ReadError {
 /// ## Errors
 ///
 /// - Returns `ReadError::Io` if reading from `stdin` fails.
-/// - Returns `ReadError::Parse` if parsing into `T` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed.
+/// - Returns `ReadError::Parse` with the trimmed input and the `FromStr`
+///   error if parsing into `T` fails.
+///
+/// `T::Err` only needs to convert into a boxed error, so any error type
+/// implementing [`std::error::Error`] works, as do plain `String` messages.
 pub fn read<T>(prompt: &str) -> Result<T, ReadError>
 where
     T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    Input::stdin().read(prompt)
+}
+
+/// Read an optional value from standard input.
+///
+/// An empty line (after trimming) yields `Ok(None)`, and any other input is
+/// parsed into `T` as with [`read`].
+///
+/// ## Example
+///
+/// ```no_run
+/// let middle: Option<String> = tinyinput::read_opt("Middle name (optional): ").unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed.
+/// - Returns `ReadError::Parse` if non-empty input cannot be parsed into `T`.
+pub fn read_opt<T>(prompt: &str) -> Result<Option<T>, ReadError>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    Input::stdin().read_opt(prompt)
+}
+
+/// Read a line from standard input and parse it into a `Vec<T>`.
+///
+/// The trimmed line is split with `delimiter`, each item is trimmed, and
+/// every item is parsed with the `FromStr` implementation of `T`. An empty
+/// line yields an empty `Vec`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use tinyinput::Delimiter;
+///
+/// let scores: Vec<u32> = tinyinput::read_vec("Scores: ", Delimiter::whitespace()).unwrap();
+/// let tags: Vec<String> = tinyinput::read_vec("Tags: ", Delimiter::comma()).unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed.
+/// - Returns `ReadError::At` with `Position::Item` if an item is empty (and
+///   empty items are not skipped) or cannot be parsed into `T`.
+pub fn read_vec<T>(prompt: &str, delimiter: Delimiter) -> Result<Vec<T>, ReadError>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    Input::stdin().read_vec(prompt, delimiter)
+}
+
+/// Read a line from standard input and parse it into a tuple.
+///
+/// The line may be written as `a b`, `a, b` or `(a, b)`: surrounding
+/// parentheses are removed, and the fields are split on commas if there are
+/// any, or on whitespace otherwise. Each field is parsed with the `FromStr`
+/// implementation of its own type, inferred from the assignment context.
+///
+/// ## Example
+///
+/// ```no_run
+/// let (name, age): (String, u32) = tinyinput::read_tuple("Name and age: ").unwrap();
+/// let (x, y, z): (f64, f64, f64) = tinyinput::read_tuple("Point: ").unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed.
+/// - Returns `ReadError::Invalid` if the number of fields is wrong.
+/// - Returns `ReadError::At` with `Position::Field` if a field cannot be
+///   parsed.
+pub fn read_tuple<T>(prompt: &str) -> Result<T, ReadError>
+where
+    T: FromFields,
+{
+    Input::stdin().read_tuple(prompt)
+}
+
+/// Read a line from standard input and extract values using a pattern.
+///
+/// `pattern` is literal text with `{}` placeholders, such as
+/// `"move {} from {} to {}"`. The line must match the literal text, and the
+/// text captured by each placeholder is parsed into the corresponding tuple
+/// field, inferred from the assignment context.
+///
+/// Whitespace in the pattern matches any amount of whitespace in the input,
+/// including none. Use `{{` and `}}` for literal braces.
+///
+/// ## Example
+///
+/// ```no_run
+/// let (hour, minute): (u8, u8) = tinyinput::scan("Time: ", "{}:{}").unwrap();
+/// let (n, from, to): (u32, usize, usize) =
+///     tinyinput::scan("", "move {} from {} to {}").unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed.
+/// - Returns `ReadError::Mismatch` with the column if the line does not match
+///   the pattern.
+/// - Returns `ReadError::Invalid` if the number of placeholders does not
+///   match the tuple.
+/// - Returns `ReadError::At` with `Position::Field` if a captured value
+///   cannot be parsed.
+pub fn scan<T>(prompt: &str, pattern: &str) -> Result<T, ReadError>
+where
+    T: FromFields,
+{
+    Input::stdin().scan(prompt, pattern)
+}
+
+/// Read several lines from standard input as a single `String`.
+///
+/// `prompt` is printed once, then lines are collected until a line matching
+/// `terminator` or the end of the input. The lines are joined with `\n`,
+/// without a trailing newline, and are otherwise kept as entered.
+///
+/// ## Example
+///
+/// ```no_run
+/// use tinyinput::Terminator;
+///
+/// let notes = tinyinput::read_block("Notes (end with a blank line):\n", Terminator::Blank).unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed before any line is read.
+pub fn read_block(prompt: &str, terminator: Terminator) -> Result<String, ReadError> {
+    Input::stdin().read_block(prompt, terminator)
+}
+
+/// Read several lines from standard input, parsing each into `T`.
+///
+/// Lines are collected as with [`read_block`], then each line is trimmed and
+/// parsed with the `FromStr` implementation of `T`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use tinyinput::Terminator;
+///
+/// let values: Vec<f64> = tinyinput::read_lines("Values, one per line:\n", Terminator::Dot).unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed before any line is read.
+/// - Returns `ReadError::At` with `Position::Line` if a line cannot be
+///   parsed.
+pub fn read_lines<T>(prompt: &str, terminator: Terminator) -> Result<Vec<T>, ReadError>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
 {
-    let mut temp = String::new();
+    Input::stdin().read_lines(prompt, terminator)
+}
+
+/// Read a `rows` x `cols` matrix from standard input.
+///
+/// `prompt` is printed once, then `rows` lines are read. Each line must hold
+/// exactly `cols` whitespace-separated values, parsed with the `FromStr`
+/// implementation of `T`.
+///
+/// ## Example
+///
+/// ```no_run
+/// let m: Vec<Vec<f64>> = tinyinput::read_matrix("Enter a 3x3 matrix:\n", 3, 3).unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed before all rows are read.
+/// - Returns `ReadError::At` with `Position::Row` if a row has the wrong
+///   number of values.
+/// - Returns `ReadError::At` with `Position::Cell` if a value cannot be
+///   parsed.
+pub fn read_matrix<T>(prompt: &str, rows: usize, cols: usize) -> Result<Vec<Vec<T>>, ReadError>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    Input::stdin().read_matrix(prompt, rows, cols)
+}
+
+/// Read a `rows` x `cols` grid of characters from standard input.
+///
+/// `prompt` is printed once, then `rows` lines are read. Each line, without
+/// its line ending, must be exactly `cols` characters long. Whitespace
+/// inside a line is kept as part of the grid.
+///
+/// ## Example
+///
+/// ```no_run
+/// let maze: Vec<Vec<char>> = tinyinput::read_grid("Maze:\n", 5, 8).unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed before all rows are read.
+/// - Returns `ReadError::At` with `Position::Row` if a row has the wrong
+///   length.
+pub fn read_grid(prompt: &str, rows: usize, cols: usize) -> Result<Vec<Vec<char>>, ReadError> {
+    Input::stdin().read_grid(prompt, rows, cols)
+}
+
+/// Read a line of input from standard input, using `default` if it is empty.
+///
+/// The default is shown in the prompt, so `"Port: "` is printed as
+/// `"Port [8080]: "`. Unlike `read(..).unwrap_or_default()`, invalid
+/// non-empty input is still reported as an error.
+///
+/// ## Example
+///
+/// ```no_run
+/// let port: u16 = tinyinput::read_or("Port: ", 8080).unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed.
+/// - Returns `ReadError::Parse` if non-empty input cannot be parsed into `T`.
+pub fn read_or<T>(prompt: &str, default: T) -> Result<T, ReadError>
+where
+    T: FromStr + Display,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    Input::stdin().read_or(prompt, default)
+}
+
+/// Ask a yes/no question on standard input.
+///
+/// Accepts `y`, `yes`, `n` and `no` in any case and shows a `[y/n]` hint.
+/// Use [`Confirm`] to set a default answer, change the accepted words, or
+/// re-prompt on unrecognized answers.
+///
+/// ## Example
+///
+/// ```no_run
+/// if tinyinput::confirm("Delete the file?").unwrap() {
+///     // ...
+/// }
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed.
+/// - Returns `ReadError::Parse` if the answer is not recognized.
+pub fn confirm(prompt: &str) -> Result<bool, ReadError> {
+    Confirm::new(prompt).read()
+}
 
-    if !prompt.is_empty() {
-        print!("{}", prompt);
-        io::stdout().flush().map_err(ReadError::Io)?;
-    }
+/// Read secret input, such as a password, from standard input.
+///
+/// When `stdin` is a terminal, typed characters are not echoed, and the
+/// terminal is restored afterwards even on panic or Ctrl-C. Otherwise a
+/// plain line is read. Use [`Secret`] to print a mask character per
+/// character typed.
+///
+/// Reading into a [`SecretString`] keeps the text in a buffer that is wiped
+/// when dropped.
+///
+/// ## Example
+///
+/// ```no_run
+/// use tinyinput::SecretString;
+///
+/// let token: SecretString = tinyinput::read_secret("API token: ").unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed.
+/// - Returns `ReadError::Interrupted` if Ctrl-C is pressed.
+/// - Returns `ReadError::Parse` if the input cannot be parsed into `T`.
+pub fn read_secret<T>(prompt: &str) -> Result<T, ReadError>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    Secret::new(prompt).read()
+}
 
-    io::stdin().read_line(&mut temp).map_err(ReadError::Io)?;
+/// Show a numbered menu on standard output and read a single choice.
+///
+/// The user can enter an item's number or its text (case-insensitive, any
+/// unique prefix). Invalid choices are re-prompted. Returns the index of the
+/// chosen item and the item itself. Use [`Select`] for more options.
+///
+/// ## Example
+///
+/// ```no_run
+/// let (index, level) = tinyinput::select("Log level: ", &["debug", "info", "warn"]).unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed.
+/// - Returns `ReadError::Invalid` if `items` is empty.
+pub fn select<'a, T>(prompt: &'a str, items: &'a [T]) -> Result<(usize, &'a T), ReadError>
+where
+    T: Display,
+{
+    Select::new(prompt, items).read()
+}
 
-    temp.trim().parse::<T>().map_err(|_| ReadError::Parse)
+/// Show a numbered menu on standard output and read several choices.
+///
+/// Choices are separated by commas or whitespace and can be numbers, ranges
+/// such as `2-4`, or item text. `all` and `none` select every item or no
+/// item. Invalid input is re-prompted. Returns the indices of the selected
+/// items in ascending order. Use [`MultiSelect`] to limit how many items may
+/// be selected.
+///
+/// ## Example
+///
+/// ```no_run
+/// let chosen = tinyinput::multi_select("Features: ", &["logging", "metrics", "tracing"]).unwrap();
+/// ```
+///
+/// ## Errors
+///
+/// - Returns `ReadError::Io` if reading from `stdin` fails.
+/// - Returns `ReadError::Eof` if `stdin` is closed.
+pub fn multi_select<T>(prompt: &str, items: &[T]) -> Result<Vec<usize>, ReadError>
+where
+    T: Display,
+{
+    MultiSelect::new(prompt, items).read()
 }
@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::input::parse;
+use crate::{Position, ReadError};
+
+/// How a line is split into items by [`read_vec`](crate::read_vec).
+///
+/// Items are trimmed before parsing. By default an empty item (such as the
+/// middle of `1,,3`) is an error; use [`skip_empty`](Delimiter::skip_empty)
+/// to ignore empty items instead. Splitting on whitespace never produces
+/// empty items.
+///
+/// ## Example
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Delimiter, Input};
+///
+/// let mut input = Input::new(Cursor::new("rust, cli ,,tools\n"), std::io::sink());
+/// let tags: Vec<String> = input
+///     .read_vec("Tags: ", Delimiter::comma().skip_empty())
+///     .unwrap();
+///
+/// assert_eq!(tags, ["rust", "cli", "tools"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delimiter {
+    separator: Separator,
+    skip_empty: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Separator {
+    Whitespace,
+    Text(String),
+}
+
+impl Delimiter {
+    /// Split on runs of whitespace.
+    pub fn whitespace() -> Self {
+        Delimiter {
+            separator: Separator::Whitespace,
+            skip_empty: false,
+        }
+    }
+
+    /// Split on `,`.
+    pub fn comma() -> Self {
+        Delimiter::custom(",")
+    }
+
+    /// Split on `;`.
+    pub fn semicolon() -> Self {
+        Delimiter::custom(";")
+    }
+
+    /// Split on every occurrence of `separator`.
+    ///
+    /// An empty `separator` splits on whitespace.
+    pub fn custom(separator: impl Into<String>) -> Self {
+        let separator = separator.into();
+
+        if separator.is_empty() {
+            return Delimiter::whitespace();
+        }
+
+        Delimiter {
+            separator: Separator::Text(separator),
+            skip_empty: false,
+        }
+    }
+
+    /// Ignore empty items instead of reporting them as errors.
+    pub fn skip_empty(mut self) -> Self {
+        self.skip_empty = true;
+        self
+    }
+
+    /// Split an already-trimmed line into trimmed items.
+    fn split<'t>(&self, line: &'t str) -> Vec<&'t str> {
+        if line.is_empty() {
+            return Vec::new();
+        }
+
+        match &self.separator {
+            Separator::Whitespace => line.split_whitespace().collect(),
+            Separator::Text(separator) => line.split(separator.as_str()).map(str::trim).collect(),
+        }
+    }
+}
+
+impl Default for Delimiter {
+    fn default() -> Self {
+        Delimiter::whitespace()
+    }
+}
+
+/// Split an already-trimmed line with `delimiter` and parse every item.
+///
+/// Errors are wrapped in `ReadError::At` with the item's position in the
+/// line, counting skipped empty items.
+pub(crate) fn parse_items<T>(line: &str, delimiter: &Delimiter) -> Result<Vec<T>, ReadError>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    let mut values = Vec::new();
+
+    for (index, item) in delimiter.split(line).into_iter().enumerate() {
+        let position = Position::Item(index + 1);
+
+        if item.is_empty() {
+            if delimiter.skip_empty {
+                continue;
+            }
+            let err = ReadError::Invalid("item is empty".to_string());
+            return Err(ReadError::at(position, err));
+        }
+
+        values.push(parse(item).map_err(|err| ReadError::at(position, err))?);
+    }
+
+    Ok(values)
+}
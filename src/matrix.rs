@@ -0,0 +1,48 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use crate::input::parse;
+use crate::{Position, ReadError};
+
+/// Parse the whitespace-separated values of matrix row `row` (1-based).
+pub(crate) fn parse_row<T>(line: &str, row: usize, cols: usize) -> Result<Vec<T>, ReadError>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    let values: Vec<&str> = line.split_whitespace().collect();
+    check_width(values.len(), row, cols, "values")?;
+
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| {
+            parse(value).map_err(|err| {
+                ReadError::at(
+                    Position::Cell {
+                        row,
+                        col: index + 1,
+                    },
+                    err,
+                )
+            })
+        })
+        .collect()
+}
+
+/// Split grid row `row` (1-based), without its line ending, into characters.
+pub(crate) fn grid_row(line: &str, row: usize, cols: usize) -> Result<Vec<char>, ReadError> {
+    let chars: Vec<char> = line.chars().collect();
+    check_width(chars.len(), row, cols, "characters")?;
+
+    Ok(chars)
+}
+
+fn check_width(found: usize, row: usize, cols: usize, what: &str) -> Result<(), ReadError> {
+    if found == cols {
+        return Ok(());
+    }
+
+    let err = ReadError::Invalid(format!("expected {} {}, found {}", cols, what, found));
+    Err(ReadError::at(Position::Row(row), err))
+}
@@ -0,0 +1,521 @@
+use std::io::{BufRead, Write};
+
+use crate::fuzzy::fuzzy_rank;
+use crate::keys::{read_key, Key};
+use crate::term::{self, RawMode, Signal};
+use crate::unicode::{fit_end, width};
+use crate::{Input, ReadError};
+
+const REVERSE: &str = "\x1b[7m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+const MATCHED: &str = "\x1b[1;4m";
+const UNMATCHED: &str = "\x1b[22;24m";
+
+/// An arrow-key menu drawn in place while the terminal is in raw mode.
+///
+/// Typing filters the items with [`fuzzy_rank`]; the cursor moves over the
+/// filtered items.
+pub(crate) struct Menu<'m> {
+    prompt: &'m str,
+    labels: &'m [String],
+    multi: bool,
+    page_size: usize,
+    query: String,
+    /// Filtered items as `(index into labels, matched character positions)`.
+    visible: Vec<(usize, Vec<usize>)>,
+    /// Position of the highlight in `visible`.
+    cursor: usize,
+    offset: usize,
+    checked: Vec<bool>,
+    message: Option<String>,
+    /// Lines drawn by the previous frame, so it can be cleared.
+    lines: usize,
+}
+
+impl<'m> Menu<'m> {
+    /// A menu choosing one of `labels`.
+    pub(crate) fn single(prompt: &'m str, labels: &'m [String], page_size: usize) -> Self {
+        Menu::new(prompt, labels, page_size, false)
+    }
+
+    /// A menu toggling any number of `labels`.
+    pub(crate) fn multi(prompt: &'m str, labels: &'m [String], page_size: usize) -> Self {
+        Menu::new(prompt, labels, page_size, true)
+    }
+
+    fn new(prompt: &'m str, labels: &'m [String], page_size: usize, multi: bool) -> Self {
+        let (_, rows) = term::size();
+
+        Menu {
+            prompt,
+            labels,
+            multi,
+            page_size: page_size.min(rows.saturating_sub(3)).max(1),
+            query: String::new(),
+            visible: (0..labels.len()).map(|index| (index, Vec::new())).collect(),
+            cursor: 0,
+            offset: 0,
+            checked: vec![false; labels.len()],
+            message: None,
+            lines: 0,
+        }
+    }
+
+    /// Run the menu while the terminal is in `raw` mode until a choice is
+    /// made, returning the chosen indices.
+    ///
+    /// `check` validates a multi-select choice before it is accepted; its
+    /// error is shown below the menu.
+    pub(crate) fn run<R, W, F>(
+        mut self,
+        input: &mut Input<R, W>,
+        raw: &mut RawMode,
+        check: F,
+    ) -> Result<Vec<usize>, ReadError>
+    where
+        R: BufRead,
+        W: Write,
+        F: Fn(&[usize]) -> Result<(), ReadError>,
+    {
+        // Lines before the last newline are printed once and never redrawn.
+        if let Some(end) = self.prompt.rfind('\n') {
+            write!(input.writer_mut(), "{}", &self.prompt[..=end]).map_err(ReadError::Io)?;
+            self.prompt = &self.prompt[end + 1..];
+        }
+
+        raw.hide_cursor().map_err(ReadError::Io)?;
+
+        let result = loop {
+            self.draw(input.writer_mut())?;
+
+            let key = match read_key(input.reader_mut(), raw).map_err(ReadError::Io)? {
+                Some(key) => key,
+                None => break Err(ReadError::Eof),
+            };
+
+            match key {
+                Key::Up | Key::Ctrl('p') => self.move_by(-1),
+                Key::Down | Key::Ctrl('n') => self.move_by(1),
+                Key::PageUp => self.move_to(self.cursor.saturating_sub(self.page_size)),
+                Key::PageDown => self.move_to(self.cursor + self.page_size),
+                Key::Home => self.move_to(0),
+                Key::End => self.move_to(self.visible.len().saturating_sub(1)),
+                Key::Char(' ') if self.multi => {
+                    if let Some(index) = self.current() {
+                        self.checked[index] ^= true;
+                        self.message = None;
+                    }
+                }
+                Key::Enter if !self.multi => {
+                    if let Some(index) = self.current() {
+                        break Ok(vec![index]);
+                    }
+                }
+                Key::Enter => {
+                    let chosen = self.chosen();
+                    match check(&chosen) {
+                        Ok(()) => break Ok(chosen),
+                        Err(err) => self.message = Some(err.to_string()),
+                    }
+                }
+                Key::Ctrl('c') | Key::Esc => break Err(ReadError::Interrupted),
+                Key::Ctrl('z') | Key::Ctrl('\\') => {
+                    let signal = match key {
+                        Key::Ctrl('z') => Signal::Stop,
+                        _ => Signal::Quit,
+                    };
+                    raw.raise(signal).map_err(ReadError::Io)?;
+                    // The shell has printed below the menu; redraw it there.
+                    self.lines = 0;
+                }
+                Key::Char(c) => {
+                    self.query.push(c);
+                    self.filter();
+                }
+                Key::Backspace => {
+                    self.query.pop();
+                    self.filter();
+                }
+                Key::Ctrl('u') => {
+                    self.query.clear();
+                    self.filter();
+                }
+                _ => {}
+            }
+        };
+
+        self.finish(input.writer_mut(), result.as_deref().ok())?;
+
+        result
+    }
+
+    /// The label index under the highlight, if any item is visible.
+    fn current(&self) -> Option<usize> {
+        self.visible.get(self.cursor).map(|(index, _)| *index)
+    }
+
+    /// Recompute the visible items for the current query.
+    fn filter(&mut self) {
+        self.visible = fuzzy_rank(&self.query, self.labels)
+            .into_iter()
+            .map(|(index, m)| (index, m.positions))
+            .collect();
+        self.cursor = 0;
+        self.offset = 0;
+    }
+
+    fn chosen(&self) -> Vec<usize> {
+        (0..self.labels.len())
+            .filter(|&index| self.checked[index])
+            .collect()
+    }
+
+    /// Move the cursor by `delta`, wrapping around at either end.
+    fn move_by(&mut self, delta: isize) {
+        let len = self.visible.len() as isize;
+        if len > 0 {
+            self.move_to((self.cursor as isize + delta).rem_euclid(len) as usize);
+        }
+    }
+
+    /// Move the cursor to `index`, scrolling it into view.
+    fn move_to(&mut self, index: usize) {
+        self.cursor = index.min(self.visible.len().saturating_sub(1));
+
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.cursor >= self.offset + self.page_size {
+            self.offset = self.cursor + 1 - self.page_size;
+        }
+    }
+
+    /// Redraw the menu over the previous frame.
+    ///
+    /// Every line is cut to fit the terminal, so that it takes exactly one
+    /// row and [`clear`](Menu::clear) can erase it again.
+    fn draw<W: Write>(&mut self, writer: &mut W) -> Result<(), ReadError> {
+        let color = term::color_enabled();
+        let (cols, _) = term::size();
+        let max = cols.saturating_sub(1);
+        let mut frame = Vec::new();
+
+        let hint = match (self.query.is_empty(), self.multi) {
+            (false, _) => "",
+            (true, true) => "(type to filter, space to toggle, enter to confirm)",
+            (true, false) => "(type to filter, arrows to move, enter to choose)",
+        };
+        let header = fit(&format!("{}{}", self.prompt, self.query), max);
+        if hint.is_empty() || width(&header) + width(hint) > max {
+            frame.push(header);
+        } else {
+            frame.push(format!("{}{}", header, style(hint, DIM, color)));
+        }
+
+        let end = (self.offset + self.page_size).min(self.visible.len());
+        for (row, (index, positions)) in self.visible[self.offset..end].iter().enumerate() {
+            let current = self.offset + row == self.cursor;
+            let marker = if current { ">" } else { " " };
+            let check = match (self.multi, self.checked[*index]) {
+                (false, _) => "",
+                (true, true) => "[x] ",
+                (true, false) => "[ ] ",
+            };
+            let prefix = format!("{} {}", marker, check);
+            let label = highlight(
+                &self.labels[*index],
+                positions,
+                max.saturating_sub(width(&prefix)),
+                color,
+            );
+            let line = format!("{}{}", prefix, label);
+
+            frame.push(if current {
+                style(&line, REVERSE, color)
+            } else {
+                line
+            });
+        }
+
+        if self.visible.is_empty() {
+            frame.push(style(&fit("  (no matches)", max), DIM, color));
+        } else if self.visible.len() > self.page_size {
+            let status = format!("({}/{})", self.cursor + 1, self.visible.len());
+            frame.push(style(&fit(&status, max), DIM, color));
+        }
+        if let Some(message) = &self.message {
+            frame.push(fit(message, max));
+        }
+
+        self.clear(writer)?;
+        write!(writer, "{}", frame.join("\n")).map_err(ReadError::Io)?;
+        writer.flush().map_err(ReadError::Io)?;
+        self.lines = frame.len();
+
+        Ok(())
+    }
+
+    /// Erase the previous frame, leaving the cursor where it started.
+    fn clear<W: Write>(&mut self, writer: &mut W) -> Result<(), ReadError> {
+        if self.lines > 1 {
+            write!(writer, "\r\x1b[{}A", self.lines - 1).map_err(ReadError::Io)?;
+        }
+        write!(writer, "\r\x1b[J").map_err(ReadError::Io)?;
+        self.lines = 0;
+
+        Ok(())
+    }
+
+    /// Replace the menu with the prompt and the chosen items.
+    fn finish<W: Write>(
+        &mut self,
+        writer: &mut W,
+        chosen: Option<&[usize]>,
+    ) -> Result<(), ReadError> {
+        self.clear(writer)?;
+
+        let answer: Vec<&str> = chosen
+            .unwrap_or_default()
+            .iter()
+            .map(|&index| self.labels[index].as_str())
+            .collect();
+        writeln!(writer, "{}{}", self.prompt, answer.join(", ")).map_err(ReadError::Io)?;
+        writer.flush().map_err(ReadError::Io)
+    }
+}
+
+fn style(text: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("{}{}{}", code, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+/// The end of the part of `text` shown in `max` columns, leaving room for a
+/// `…` if it has to be cut.
+fn cut(text: &str, max: usize) -> usize {
+    if width(text) <= max {
+        text.len()
+    } else {
+        fit_end(text, max.saturating_sub(1))
+    }
+}
+
+/// Shorten `text` to at most `max` columns, ending it with `…` if cut.
+fn fit(text: &str, max: usize) -> String {
+    let end = cut(text, max);
+
+    if end < text.len() && max > 0 {
+        format!("{}…", &text[..end])
+    } else {
+        text[..end].to_string()
+    }
+}
+
+/// Shorten `text` to at most `max` columns, so it fits on one line, and
+/// emphasize the characters at `positions`.
+fn highlight(text: &str, positions: &[usize], max: usize, color: bool) -> String {
+    let end = cut(text, max);
+    let mut out = String::new();
+
+    for (index, c) in text[..end].chars().enumerate() {
+        if color && positions.contains(&index) {
+            out.push_str(MATCHED);
+            out.push(c);
+            out.push_str(UNMATCHED);
+        } else {
+            out.push(c);
+        }
+    }
+
+    if end < text.len() && max > 0 {
+        out.push('…');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const UP: &str = "\x1b[A";
+    const DOWN: &str = "\x1b[B";
+    const PAGE_DOWN: &str = "\x1b[6~";
+    const END: &str = "\x1b[F";
+
+    fn labels(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    /// Run `menu` by feeding it `keys`, returning the result and the output.
+    fn run<F>(mut menu: Menu<'_>, keys: &str, check: F) -> (Result<Vec<usize>, ReadError>, String)
+    where
+        F: Fn(&[usize]) -> Result<(), ReadError>,
+    {
+        // The page size is limited by the size of the terminal running the tests.
+        menu.page_size = 3;
+
+        let mut input = Input::new(Cursor::new(keys.as_bytes()), Vec::new());
+        let result = menu.run(&mut input, &mut RawMode::inert(), check);
+        let output = String::from_utf8_lossy(input.writer()).into_owned();
+
+        (result, output)
+    }
+
+    fn choose(items: &[&str], keys: &str) -> Result<Vec<usize>, ReadError> {
+        let labels = labels(items);
+        run(Menu::single("Pick: ", &labels, 3), keys, |_| Ok(())).0
+    }
+
+    fn toggle(items: &[&str], keys: &str) -> Result<Vec<usize>, ReadError> {
+        let labels = labels(items);
+        run(Menu::multi("Pick: ", &labels, 3), keys, |_| Ok(())).0
+    }
+
+    const FRUITS: [&str; 3] = ["apple", "banana", "cherry"];
+
+    #[test]
+    fn arrows_move_and_wrap_around() {
+        assert_eq!(choose(&FRUITS, "\r").unwrap(), [0]);
+        assert_eq!(
+            choose(&FRUITS, &format!("{}{}\r", DOWN, DOWN)).unwrap(),
+            [2]
+        );
+        assert_eq!(choose(&FRUITS, &format!("{}\r", UP)).unwrap(), [2]);
+        assert_eq!(choose(&FRUITS, "\x0e\x0e\x0e\x10\r").unwrap(), [2]);
+    }
+
+    #[test]
+    fn typing_filters_the_items() {
+        assert_eq!(choose(&FRUITS, "ch\r").unwrap(), [2]);
+        assert_eq!(choose(&FRUITS, "an\r").unwrap(), [1]);
+        assert_eq!(choose(&FRUITS, "chx\x7f\r").unwrap(), [2]);
+        assert_eq!(choose(&FRUITS, "ch\x15\r").unwrap(), [0]);
+
+        // Enter does nothing while nothing matches.
+        assert!(matches!(choose(&FRUITS, "zz\r"), Err(ReadError::Eof)));
+        assert_eq!(choose(&FRUITS, "zz\r\x15\r").unwrap(), [0]);
+    }
+
+    #[test]
+    fn long_lists_scroll() {
+        let labels = labels(&["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"]);
+        let mut menu = Menu::single("", &labels, 3);
+        menu.page_size = 3;
+
+        menu.move_by(4);
+        assert_eq!((menu.cursor, menu.offset), (4, 2));
+        menu.move_to(1);
+        assert_eq!((menu.cursor, menu.offset), (1, 1));
+        menu.move_by(-2);
+        assert_eq!((menu.cursor, menu.offset), (9, 7));
+        menu.move_to(20);
+        assert_eq!((menu.cursor, menu.offset), (9, 7));
+
+        let items: Vec<&str> = labels.iter().map(String::as_str).collect();
+        assert_eq!(choose(&items, &format!("{}\r", PAGE_DOWN)).unwrap(), [3]);
+        assert_eq!(choose(&items, &format!("{}\r", END)).unwrap(), [9]);
+
+        let (_, output) = run(Menu::single("", &labels, 3), DOWN, |_| Ok(()));
+        assert!(output.contains("(2/10)"));
+    }
+
+    #[test]
+    fn space_toggles_items() {
+        let keys = format!(" {}{} \r", DOWN, DOWN);
+        assert_eq!(toggle(&FRUITS, &keys).unwrap(), [0, 2]);
+        assert_eq!(toggle(&FRUITS, "  \r").unwrap(), []);
+
+        // Items stay checked when the filter changes.
+        assert_eq!(toggle(&FRUITS, "ch \x15 \r").unwrap(), [0, 2]);
+    }
+
+    #[test]
+    fn check_errors_are_shown_until_accepted() {
+        let labels = labels(&FRUITS);
+        let at_least_one = |chosen: &[usize]| match chosen.is_empty() {
+            true => Err(ReadError::Invalid("choose at least one".to_string())),
+            false => Ok(()),
+        };
+
+        let (result, output) = run(Menu::multi("Pick: ", &labels, 3), "\r \r", at_least_one);
+        assert_eq!(result.unwrap(), [0]);
+        assert!(output.contains("choose at least one"));
+        assert!(output.ends_with("Pick: apple\n"));
+    }
+
+    #[test]
+    fn every_line_fits_in_one_row() {
+        let (cols, _) = term::size();
+        let prompt = "Which of these many options would you like to pick? ".repeat(4);
+        let labels = vec!["漢字".repeat(cols), "🎉".repeat(cols), "plain".to_string()];
+
+        let mut menu = Menu::multi(&prompt, &labels, 3);
+        menu.message = Some("select at most one option, ".repeat(10));
+
+        let mut frame = Vec::new();
+        menu.draw(&mut frame).unwrap();
+        let frame = String::from_utf8(frame).unwrap();
+
+        let lines: Vec<&str> = frame.split('\n').collect();
+        assert_eq!(lines.len(), menu.lines);
+        for line in lines {
+            assert!(
+                width(line) < cols,
+                "{:?} does not fit in {} columns",
+                line,
+                cols
+            );
+        }
+    }
+
+    #[test]
+    fn labels_are_cut_by_width() {
+        assert_eq!(fit("漢字漢字", 8), "漢字漢字");
+        assert_eq!(fit("漢字漢字", 7), "漢字漢…");
+        assert_eq!(fit("漢字漢字", 6), "漢字…");
+        assert_eq!(highlight("漢字漢字", &[0, 3], 6, false), "漢字…");
+        assert_eq!(fit("abc", 0), "");
+    }
+
+    #[test]
+    fn prompt_lines_before_the_last_are_printed_once() {
+        let labels = labels(&FRUITS);
+        let keys = format!("{}{}\r", DOWN, DOWN);
+        let (result, output) = run(Menu::single("Fruit\nPick: ", &labels, 3), &keys, |_| Ok(()));
+
+        assert_eq!(result.unwrap(), [2]);
+        assert!(output.starts_with("Fruit\n"));
+        assert_eq!(output.matches("Fruit").count(), 1);
+        assert!(output.ends_with("Pick: cherry\n"));
+    }
+
+    #[test]
+    fn suspending_redraws_the_menu() {
+        let labels = labels(&FRUITS);
+        let keys = format!("{}\x1a\r", DOWN);
+        let (result, output) = run(Menu::single("Pick: ", &labels, 3), &keys, |_| Ok(()));
+
+        assert_eq!(result.unwrap(), [1]);
+        // Four frames are drawn, but the one after resuming does not move up
+        // over the rows the shell printed.
+        assert_eq!(output.matches("\r\x1b[J").count(), 4);
+        assert_eq!(output.matches("\x1b[3A").count(), 2);
+    }
+
+    #[test]
+    fn escape_and_ctrl_c_interrupt() {
+        assert!(matches!(
+            choose(&FRUITS, "\x1b"),
+            Err(ReadError::Interrupted)
+        ));
+        assert!(matches!(
+            choose(&FRUITS, "a\x03"),
+            Err(ReadError::Interrupted)
+        ));
+        assert!(matches!(toggle(&FRUITS, " "), Err(ReadError::Eof)));
+    }
+}
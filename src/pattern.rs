@@ -0,0 +1,134 @@
+use crate::ReadError;
+
+/// One part of a parsed pattern.
+#[derive(Debug, PartialEq)]
+enum Piece {
+    /// Literal text that must appear in the input.
+    Text(String),
+    /// A run of whitespace, matching any amount of whitespace (even none).
+    Space,
+    /// A `{}` placeholder capturing one value.
+    Value,
+}
+
+/// Split a pattern into literal text, whitespace and placeholders.
+///
+/// `{{` and `}}` stand for literal braces. Any other brace that is not part
+/// of `{}` is kept as literal text.
+fn pieces(pattern: &str) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let piece = match c {
+            '{' if chars.peek() == Some(&'}') => Piece::Value,
+            c if c.is_whitespace() => {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                Piece::Space
+            }
+            '{' | '}' if chars.peek() == Some(&c) => {
+                chars.next();
+                text.push(c);
+                continue;
+            }
+            c => {
+                text.push(c);
+                continue;
+            }
+        };
+
+        if piece == Piece::Value {
+            chars.next();
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(std::mem::take(&mut text)));
+        }
+        pieces.push(piece);
+    }
+
+    if !text.is_empty() {
+        pieces.push(Piece::Text(text));
+    }
+
+    pieces
+}
+
+/// Match `line` against `pattern`, returning the text of every placeholder.
+///
+/// Leading and trailing whitespace in `line` is ignored. Captured values are
+/// trimmed.
+pub(crate) fn captures<'t>(pattern: &str, line: &'t str) -> Result<Vec<&'t str>, ReadError> {
+    let pieces = pieces(pattern);
+    let end = line.trim_end().len();
+    let mut pos = skip_space(line, 0, end);
+    let mut values = Vec::new();
+
+    for (index, piece) in pieces.iter().enumerate() {
+        match piece {
+            Piece::Space => pos = skip_space(line, pos, end),
+            Piece::Text(text) => {
+                if !line[pos..end].starts_with(text.as_str()) {
+                    return Err(mismatch(line, pos, end, format!("'{}'", text)));
+                }
+                pos += text.len();
+            }
+            Piece::Value => {
+                pos = skip_space(line, pos, end);
+                let rest = &line[pos..end];
+                let len = value_len(rest, &pieces[index + 1..]);
+                let value = rest[..len].trim_end();
+
+                if value.is_empty() {
+                    return Err(mismatch(line, pos, end, "a value".to_string()));
+                }
+                values.push(value);
+                pos += len;
+            }
+        }
+    }
+
+    pos = skip_space(line, pos, end);
+    if pos < end {
+        return Err(mismatch(line, pos, end, "end of line".to_string()));
+    }
+
+    Ok(values)
+}
+
+/// The length of the value at the start of `rest`, given the pieces that
+/// follow its placeholder.
+///
+/// The value runs up to the next literal text, or up to whitespace if the
+/// placeholder is followed by whitespace or another placeholder. The last
+/// placeholder takes the rest of the line.
+fn value_len(rest: &str, next: &[Piece]) -> usize {
+    let (stop_at_space, text) = match next {
+        [] => return rest.len(),
+        [Piece::Text(text), ..] => (false, Some(text)),
+        [Piece::Space, Piece::Text(text), ..] => (true, Some(text)),
+        _ => (true, None),
+    };
+
+    let space = rest.find(char::is_whitespace).filter(|_| stop_at_space);
+    let text = text.and_then(|text| rest.find(text.as_str()));
+
+    match (space, text) {
+        (Some(a), Some(b)) => a.min(b),
+        (a, b) => a.or(b).unwrap_or(rest.len()),
+    }
+}
+
+/// Advance `pos` past any whitespace before `end`.
+fn skip_space(line: &str, pos: usize, end: usize) -> usize {
+    let rest = &line[pos..end];
+    pos + rest.len() - rest.trim_start().len()
+}
+
+fn mismatch(line: &str, pos: usize, end: usize, expected: String) -> ReadError {
+    ReadError::Mismatch {
+        column: line[..pos].chars().count() + 1,
+        expected,
+        found: line[pos..end].to_string(),
+    }
+}
@@ -0,0 +1,436 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use crate::editor::LineOptions;
+use crate::input::parse;
+use crate::{Completer, Hinter, Input, ReadError};
+
+/// Placeholder in retry messages that is replaced by the error text.
+const ERROR_PLACEHOLDER: &str = "{error}";
+
+/// Retry settings shared by prompts that can re-ask on invalid input.
+#[derive(Debug, Clone)]
+pub(crate) struct Retry {
+    enabled: bool,
+    max_attempts: Option<usize>,
+    message: String,
+}
+
+impl Retry {
+    /// Retrying disabled, with the default error message.
+    pub(crate) fn new() -> Self {
+        Retry {
+            enabled: false,
+            max_attempts: None,
+            message: ERROR_PLACEHOLDER.to_string(),
+        }
+    }
+
+    /// Retry until the input is valid.
+    pub(crate) fn forever(&mut self) {
+        self.enabled = true;
+        self.max_attempts = None;
+    }
+
+    /// Retry until the input is valid or `attempts` attempts were made.
+    pub(crate) fn limit(&mut self, attempts: usize) {
+        self.enabled = true;
+        self.max_attempts = Some(attempts.max(1));
+    }
+
+    pub(crate) fn set_message(&mut self, message: String) {
+        self.message = message;
+    }
+
+    /// Call `attempt` until it succeeds or fails with an error that must not
+    /// be retried, printing the error message between attempts.
+    pub(crate) fn run<R, W, T, F>(
+        &self,
+        input: &mut Input<R, W>,
+        mut attempt: F,
+    ) -> Result<T, ReadError>
+    where
+        R: BufRead,
+        W: Write,
+        F: FnMut(&mut Input<R, W>) -> Result<T, ReadError>,
+    {
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            match attempt(input) {
+                Ok(value) => return Ok(value),
+                Err(err) if self.should_retry(&err, attempts) => {
+                    let message = self.message.replace(ERROR_PLACEHOLDER, &err.to_string());
+                    writeln!(input.writer_mut(), "{}", message).map_err(ReadError::Io)?;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn should_retry(&self, err: &ReadError, attempts: usize) -> bool {
+        self.enabled && err.is_retryable() && self.max_attempts.map_or(true, |max| attempts < max)
+    }
+}
+
+/// A configurable prompt that reads and parses a single value.
+///
+/// `Prompt` builds on the same `FromStr`-based parsing as [`read`](crate::read)
+/// and adds optional re-prompting when the input cannot be parsed. The target
+/// type is still inferred from the assignment context.
+///
+/// ## Example
+///
+/// ```no_run
+/// use tinyinput::Prompt;
+///
+/// let age: u32 = Prompt::new("Age: ").retry().read().unwrap();
+/// ```
+///
+/// ## Retrying
+///
+/// With [`retry`](Prompt::retry) or [`attempts`](Prompt::attempts), a
+/// `ReadError::Parse` or `ReadError::Invalid` prints the error message and
+/// asks again. I/O errors and `ReadError::Eof` are always returned immediately.
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Input, Prompt};
+///
+/// let mut input = Input::new(Cursor::new("abc\n42\n"), Vec::new());
+/// let n: i32 = Prompt::new("Number: ")
+///     .retry()
+///     .error_message("Please enter a number ({error})")
+///     .read_from(&mut input)
+///     .unwrap();
+///
+/// assert_eq!(n, 42);
+/// assert_eq!(
+///     String::from_utf8_lossy(input.writer()),
+///     "Number: Please enter a number ('abc' is not valid: invalid digit found in string)\nNumber: ",
+/// );
+/// ```
+///
+/// ## Validation
+///
+/// Values that parse successfully can still be rejected by validators added
+/// with [`validate`](Prompt::validate). See the [`validate`](crate::validate)
+/// module for built-in validators.
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Input, Prompt, ReadError};
+///
+/// let mut input = Input::new(Cursor::new("  \n"), std::io::sink());
+/// let err = Prompt::new("Name: ")
+///     .validate(|name: &String| {
+///         if name.is_empty() {
+///             Err("name must not be empty".to_string())
+///         } else {
+///             Ok(())
+///         }
+///     })
+///     .read_from(&mut input)
+///     .unwrap_err();
+///
+/// assert!(matches!(err, ReadError::Invalid(message) if message == "name must not be empty"));
+/// ```
+///
+/// ## Default values
+///
+/// A default set with [`default`](Prompt::default) is shown in the prompt and
+/// used when the entered line is empty. Non-empty input is parsed as usual.
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Input, Prompt};
+///
+/// let mut input = Input::new(Cursor::new("\n"), Vec::new());
+/// let port: u16 = Prompt::new("Port: ").default(8080).read_from(&mut input).unwrap();
+///
+/// assert_eq!(port, 8080);
+/// assert_eq!(input.writer(), b"Port [8080]: ");
+/// ```
+pub struct Prompt<'a, T> {
+    text: &'a str,
+    retry: Retry,
+    validators: Vec<Validator<'a, T>>,
+    /// The default as shown in the prompt, and a function returning it.
+    default: Option<(String, DefaultValue<'a, T>)>,
+    line: LineOptions<'a>,
+}
+
+type Validator<'a, T> = Box<dyn Fn(&T) -> Result<(), String> + 'a>;
+
+type DefaultValue<'a, T> = Box<dyn Fn() -> T + 'a>;
+
+impl<'a, T> Prompt<'a, T> {
+    /// Create a prompt that prints `text` before reading.
+    ///
+    /// Retrying is disabled by default, so the prompt behaves like
+    /// [`read`](crate::read) until configured otherwise.
+    pub fn new(text: &'a str) -> Self {
+        Prompt {
+            text,
+            retry: Retry::new(),
+            validators: Vec::new(),
+            default: None,
+            line: LineOptions::default(),
+        }
+    }
+
+    /// Use `value` when the entered line is empty, and show it in the prompt.
+    ///
+    /// The default is rendered into the prompt text with `Display`, so
+    /// `"Port: "` becomes `"Port [8080]: "`. A copy of `value` itself is
+    /// returned on an empty line, after being checked by the validators like
+    /// any other input, so its `Display` form does not need to parse.
+    ///
+    /// ```
+    /// use std::fmt;
+    /// use std::io::Cursor;
+    /// use std::str::FromStr;
+    /// use tinyinput::{Input, Prompt};
+    ///
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum Level {
+    ///     Info,
+    ///     Debug,
+    /// }
+    ///
+    /// impl fmt::Display for Level {
+    ///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    ///         match self {
+    ///             Level::Info => write!(f, "Info level"),
+    ///             Level::Debug => write!(f, "Debug level"),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// impl FromStr for Level {
+    ///     type Err = String;
+    ///
+    ///     fn from_str(s: &str) -> Result<Self, String> {
+    ///         match s {
+    ///             "info" => Ok(Level::Info),
+    ///             "debug" => Ok(Level::Debug),
+    ///             _ => Err("unknown level".to_string()),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut input = Input::new(Cursor::new("\n"), Vec::new());
+    /// let level = Prompt::new("Level:\n")
+    ///     .default(Level::Info)
+    ///     .read_from(&mut input)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(level, Level::Info);
+    /// assert_eq!(input.writer(), b"Level [Info level]:\n");
+    /// ```
+    pub fn default(mut self, value: T) -> Self
+    where
+        T: Display + Clone + 'a,
+    {
+        self.default = Some((value.to_string(), Box::new(move || value.clone())));
+        self
+    }
+
+    /// Add a validator that runs after the input is parsed.
+    ///
+    /// Validators run in the order they were added. The first one to return
+    /// `Err(message)` makes the read fail with `ReadError::Invalid(message)`,
+    /// which is re-prompted like a parse error when retrying is enabled.
+    pub fn validate<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&T) -> Result<(), String> + 'a,
+    {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Recall and record lines in the history namespace `namespace`.
+    ///
+    /// Prompts use the default namespace `""` unless set otherwise, so
+    /// prompts that ask for different kinds of values should use their own
+    /// namespaces. See [`History`](crate::History).
+    pub fn history(mut self, namespace: &'a str) -> Self {
+        self.line.history = Some(namespace);
+        self
+    }
+
+    /// Keep the entered lines out of the history, for sensitive values that
+    /// are not read with [`Secret`](crate::Secret).
+    pub fn no_history(mut self) -> Self {
+        self.line.history = None;
+        self
+    }
+
+    /// Complete the line with `completer` when Tab is pressed.
+    ///
+    /// Completion only happens when reading from a terminal. See
+    /// [`Completer`] for how candidates are inserted.
+    pub fn completer(mut self, completer: &'a dyn Completer) -> Self {
+        self.line.completer = Some(completer);
+        self
+    }
+
+    /// Show suggestions from `hinter` after the cursor while typing.
+    ///
+    /// Hints only appear when reading from a terminal. Use
+    /// [`HistoryHinter`](crate::HistoryHinter) for fish-style suggestions
+    /// from the prompt's history.
+    pub fn hinter(mut self, hinter: &'a dyn Hinter) -> Self {
+        self.line.hinter = Some(hinter);
+        self
+    }
+
+    /// Re-prompt on invalid input until a valid value is entered.
+    pub fn retry(mut self) -> Self {
+        self.retry.forever();
+        self
+    }
+
+    /// Re-prompt on invalid input, giving up after `attempts` attempts.
+    ///
+    /// At least one attempt is always made. When the last attempt fails, its
+    /// error is returned.
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.retry.limit(attempts);
+        self
+    }
+
+    /// Set the message printed before re-prompting.
+    ///
+    /// Any `{error}` in `message` is replaced by the error's `Display` text.
+    /// The default message is just the error text.
+    pub fn error_message(mut self, message: impl Into<String>) -> Self {
+        self.retry.set_message(message.into());
+        self
+    }
+}
+
+impl<T> Prompt<'_, T>
+where
+    T: FromStr,
+    T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+    /// Prompt on `stdout` and read the value from `stdin`.
+    ///
+    /// ## Errors
+    ///
+    /// - Returns `ReadError::Io` if reading or writing fails.
+    /// - Returns `ReadError::Eof` if `stdin` is closed.
+    /// - Returns `ReadError::Parse` if the input cannot be parsed and no
+    ///   attempts are left.
+    /// - Returns `ReadError::Invalid` if a validator rejects the value and no
+    ///   attempts are left.
+    pub fn read(&self) -> Result<T, ReadError> {
+        self.read_from(&mut Input::stdin())
+    }
+
+    /// Prompt and read the value using an existing [`Input`] handle.
+    ///
+    /// Errors are the same as for [`read`](Prompt::read).
+    pub fn read_from<R, W>(&self, input: &mut Input<R, W>) -> Result<T, ReadError>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        let text = self.text();
+
+        self.retry.run(input, |input| {
+            let line = input.read_line_with(&text, self.line)?;
+            self.parse(line.trim())
+        })
+    }
+
+    /// Prompt on `stdout` and read an optional value from `stdin`.
+    ///
+    /// An empty line (after trimming) yields `Ok(None)`. Non-empty input is
+    /// parsed and validated as with [`read`](Prompt::read). If a default is
+    /// set, an empty line yields the default instead.
+    ///
+    /// Errors are the same as for [`read`](Prompt::read).
+    pub fn read_opt(&self) -> Result<Option<T>, ReadError> {
+        self.read_opt_from(&mut Input::stdin())
+    }
+
+    /// Prompt and read an optional value using an existing [`Input`] handle.
+    ///
+    /// Behaves like [`read_opt`](Prompt::read_opt).
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::{Input, Prompt};
+    ///
+    /// let mut input = Input::new(Cursor::new("\nAnn\n"), std::io::sink());
+    /// let prompt = Prompt::<String>::new("Middle name (optional): ");
+    ///
+    /// assert_eq!(prompt.read_opt_from(&mut input).unwrap(), None);
+    /// assert_eq!(prompt.read_opt_from(&mut input).unwrap(), Some("Ann".to_string()));
+    /// ```
+    pub fn read_opt_from<R, W>(&self, input: &mut Input<R, W>) -> Result<Option<T>, ReadError>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        let text = self.text();
+
+        self.retry.run(input, |input| {
+            let line = input.read_line_with(&text, self.line)?;
+
+            match line.trim() {
+                "" if self.default.is_none() => Ok(None),
+                line => self.parse(line).map(Some),
+            }
+        })
+    }
+
+    /// The prompt text, including the default if one is set.
+    fn text(&self) -> Cow<'_, str> {
+        match &self.default {
+            Some((hint, _)) => Cow::Owned(with_hint(self.text, hint)),
+            None => Cow::Borrowed(self.text),
+        }
+    }
+
+    /// Parse and validate a trimmed line, substituting the default if empty.
+    fn parse(&self, line: &str) -> Result<T, ReadError> {
+        let value = match (line, &self.default) {
+            ("", Some((_, default))) => default(),
+            (line, _) => parse(line)?,
+        };
+        self.check(&value)?;
+        Ok(value)
+    }
+
+    fn check(&self, value: &T) -> Result<(), ReadError> {
+        self.validators
+            .iter()
+            .try_for_each(|validator| validator(value).map_err(ReadError::Invalid))
+    }
+}
+
+/// Insert `[hint]` into a prompt, before a trailing `:` if there is one.
+///
+/// `"Port: "` becomes `"Port [8080]: "` and `"Continue? "` becomes
+/// `"Continue? [y/N] "`. A prompt ending in a newline keeps it, so
+/// `"Name:\n"` becomes `"Name [Ann]:\n"`.
+pub(crate) fn with_hint(text: &str, hint: &str) -> String {
+    let body = text.trim_end_matches(|c: char| c.is_whitespace() || c == ':');
+    let tail = &text[body.len()..];
+    let separator = if tail.contains(':') { ":" } else { "" };
+    let end = if tail.ends_with('\n') { "\n" } else { " " };
+
+    if body.is_empty() {
+        format!("[{}]{}{}", hint, separator, end)
+    } else {
+        format!("{} [{}]{}{}", body, hint, separator, end)
+    }
+}
@@ -0,0 +1,176 @@
+use std::error::Error;
+use std::io::{self, BufRead, StdinLock};
+use std::str::FromStr;
+
+use crate::input::parse;
+use crate::ReadError;
+
+/// A whitespace-separated token reader.
+///
+/// `Scanner` splits its input on whitespace, regardless of line boundaries,
+/// and parses each token with `FromStr`. This suits contest-style input and
+/// data entry where several values share a line, or one value spans several
+/// lines.
+///
+/// Scanners do not print prompts.
+///
+/// ## Example
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::Scanner;
+///
+/// let mut scanner = Scanner::new(Cursor::new("3\n1 2\n3\nx y z\n"));
+///
+/// let n: usize = scanner.next().unwrap();
+/// let values: Vec<i64> = scanner.next_n(n).unwrap();
+/// let words: Vec<String> = scanner.next_line_vec().unwrap();
+///
+/// assert_eq!(values, [1, 2, 3]);
+/// assert_eq!(words, ["x", "y", "z"]);
+/// assert!(scanner.next::<i64>().is_err());
+/// ```
+#[derive(Debug)]
+pub struct Scanner<R> {
+    reader: R,
+    line: String,
+    pos: usize,
+}
+
+impl Scanner<StdinLock<'static>> {
+    /// Create a scanner reading from `stdin`.
+    ///
+    /// The scanner holds the `stdin` lock for as long as it is alive.
+    pub fn stdin() -> Self {
+        Scanner::new(io::stdin().lock())
+    }
+}
+
+impl<R> Scanner<R>
+where
+    R: BufRead,
+{
+    /// Create a scanner reading from `reader`.
+    pub fn new(reader: R) -> Self {
+        Scanner {
+            reader,
+            line: String::new(),
+            pos: 0,
+        }
+    }
+
+    /// Parse the next token into type `T`.
+    ///
+    /// Blank lines are skipped.
+    ///
+    /// ## Errors
+    ///
+    /// - Returns `ReadError::Io` if reading fails.
+    /// - Returns `ReadError::Eof` if the input ends before another token.
+    /// - Returns `ReadError::Parse` if the token cannot be parsed into `T`.
+    ///   The token is consumed either way.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next<T>(&mut self) -> Result<T, ReadError>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        loop {
+            if let Some(token) = self.next_token() {
+                return parse(token);
+            }
+            self.fill_line()?;
+        }
+    }
+
+    /// Parse the next `n` tokens into a `Vec<T>`.
+    ///
+    /// Tokens may span several lines. Errors are the same as for
+    /// [`next`](Scanner::next).
+    pub fn next_n<T>(&mut self, n: usize) -> Result<Vec<T>, ReadError>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        (0..n).map(|_| self.next()).collect()
+    }
+
+    /// Parse the rest of a line into a `Vec<T>`.
+    ///
+    /// If tokens remain on the current line, those are parsed. Otherwise the
+    /// next line is read and all of its tokens are parsed, so a blank line
+    /// yields an empty `Vec`.
+    ///
+    /// ## Errors
+    ///
+    /// - Returns `ReadError::Io` if reading fails.
+    /// - Returns `ReadError::Eof` if a new line is needed and the input has
+    ///   ended.
+    /// - Returns `ReadError::Parse` if a token cannot be parsed into `T`.
+    ///   The whole line is consumed either way.
+    pub fn next_line_vec<T>(&mut self) -> Result<Vec<T>, ReadError>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        if self.rest().trim().is_empty() {
+            self.fill_line()?;
+        }
+
+        let start = self.pos;
+        self.pos = self.line.len();
+
+        self.line[start..].split_whitespace().map(parse).collect()
+    }
+
+    /// Get a reference to the underlying reader.
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// Consume the scanner, returning the underlying reader.
+    ///
+    /// Any unread tokens on the current line are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// The unread part of the current line.
+    fn rest(&self) -> &str {
+        &self.line[self.pos..]
+    }
+
+    /// Take the next token from the current line, if there is one.
+    fn next_token(&mut self) -> Option<&str> {
+        let rest = &self.line[self.pos..];
+        let start = rest.len() - rest.trim_start().len();
+        let len = rest[start..]
+            .find(char::is_whitespace)
+            .unwrap_or(rest.len() - start);
+
+        if len == 0 {
+            return None;
+        }
+
+        let token_start = self.pos + start;
+        self.pos = token_start + len;
+        Some(&self.line[token_start..self.pos])
+    }
+
+    /// Replace the current line with the next one from the reader.
+    fn fill_line(&mut self) -> Result<(), ReadError> {
+        self.line.clear();
+        self.pos = 0;
+
+        if self
+            .reader
+            .read_line(&mut self.line)
+            .map_err(ReadError::Io)?
+            == 0
+        {
+            return Err(ReadError::Eof);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,523 @@
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::ptr;
+use std::str::FromStr;
+use std::sync::atomic::{self, Ordering};
+
+use crate::term::{RawMode, Signal};
+use crate::{Input, ReadError};
+
+/// Text used in place of secret input in `ReadError::Parse`.
+const HIDDEN: &str = "<hidden>";
+
+/// A prompt for secret input such as passwords and API tokens.
+///
+/// When `stdin` is a terminal, echo is turned off while the secret is typed
+/// and restored afterwards, including when a panic unwinds or Ctrl-C is
+/// pressed. Optionally, a mask character is printed for every character
+/// typed. When the input is not a terminal, a plain line is read instead.
+///
+/// The entered text is trimmed and parsed with `FromStr`, like other reads.
+/// Parse errors never contain the secret text. The text is collected in a
+/// [`SecretString`] buffer that is wiped when dropped; read into a
+/// `SecretString` to keep the secret protected after reading.
+///
+/// ## Example
+///
+/// ```no_run
+/// use tinyinput::{Secret, SecretString};
+///
+/// let password: SecretString = Secret::new("Password: ").mask('*').read().unwrap();
+/// let pin: u32 = Secret::new("PIN: ").read().unwrap();
+/// ```
+///
+/// ## Terminal keys
+///
+/// - Enter finishes the input.
+/// - Backspace deletes the last character, Ctrl-U deletes everything.
+/// - Ctrl-C stops reading and returns `ReadError::Interrupted`.
+/// - Ctrl-D on an empty input returns `ReadError::Eof`.
+/// - Ctrl-Z and Ctrl-\ suspend and quit the program as usual, with the
+///   terminal restored while it is stopped.
+#[derive(Debug, Clone)]
+pub struct Secret<'a> {
+    text: &'a str,
+    mask: Option<char>,
+}
+
+impl<'a> Secret<'a> {
+    /// Create a secret prompt that prints `text` before reading.
+    ///
+    /// Nothing is shown while typing unless a mask is set.
+    pub fn new(text: &'a str) -> Self {
+        Secret { text, mask: None }
+    }
+
+    /// Print `mask` for every character typed.
+    pub fn mask(mut self, mask: char) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Prompt on `stdout` and read the secret from `stdin`.
+    ///
+    /// ## Errors
+    ///
+    /// - Returns `ReadError::Io` if reading, writing, or changing the
+    ///   terminal mode fails.
+    /// - Returns `ReadError::Eof` if `stdin` is closed.
+    /// - Returns `ReadError::Interrupted` if Ctrl-C is pressed.
+    /// - Returns `ReadError::Parse` if the input cannot be parsed into `T`.
+    ///   The error's `input` does not contain the secret.
+    pub fn read<T>(&self) -> Result<T, ReadError>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.read_from(&mut Input::stdin())
+    }
+
+    /// Prompt and read the secret using an existing [`Input`] handle.
+    ///
+    /// Echo is only turned off for handles created with
+    /// [`Input::stdin`] when `stdin` is a terminal. Errors are the same as
+    /// for [`read`](Secret::read).
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::{Input, ReadError, Secret};
+    ///
+    /// let mut input = Input::new(Cursor::new("hunter2\n"), std::io::sink());
+    /// let err = Secret::new("PIN: ").read_from::<u32, _, _>(&mut input).unwrap_err();
+    ///
+    /// assert!(!err.to_string().contains("hunter2"));
+    /// ```
+    pub fn read_from<T, R, W>(&self, input: &mut Input<R, W>) -> Result<T, ReadError>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+        R: BufRead,
+        W: Write,
+    {
+        read_secret_line(input, self.text, self.mask)?.parse()
+    }
+}
+
+/// Print `prompt` and read a line without echoing it.
+fn read_secret_line<R, W>(
+    input: &mut Input<R, W>,
+    prompt: &str,
+    mask: Option<char>,
+) -> Result<SecretString, ReadError>
+where
+    R: BufRead,
+    W: Write,
+{
+    write!(input.writer_mut(), "{}", prompt).map_err(ReadError::Io)?;
+    input.writer_mut().flush().map_err(ReadError::Io)?;
+
+    let secret = if input.is_terminal() {
+        let raw = RawMode::enable().map_err(ReadError::Io)?;
+        let result = read_hidden(input, prompt, mask, &raw);
+        drop(raw);
+
+        writeln!(input.writer_mut()).map_err(ReadError::Io)?;
+        result?
+    } else {
+        read_plain(input.reader_mut())?
+    };
+
+    secret.finish()
+}
+
+/// Read a plain line byte by byte, so no intermediate `String` holds it.
+fn read_plain<R: BufRead>(reader: &mut R) -> Result<SecretString, ReadError> {
+    let mut secret = SecretString::new();
+
+    loop {
+        match read_byte(reader).map_err(ReadError::Io)? {
+            Some(b'\n') => break,
+            Some(byte) => secret.push(byte),
+            None if secret.is_empty() => return Err(ReadError::Eof),
+            None => break,
+        }
+    }
+
+    Ok(secret)
+}
+
+/// Read bytes in raw mode until Enter, handling editing and control keys.
+///
+/// `prompt` has already been printed, and is printed again when the process
+/// is resumed after Ctrl-Z.
+fn read_hidden<R, W>(
+    input: &mut Input<R, W>,
+    prompt: &str,
+    mask: Option<char>,
+    raw: &RawMode,
+) -> Result<SecretString, ReadError>
+where
+    R: BufRead,
+    W: Write,
+{
+    let mut secret = SecretString::new();
+
+    loop {
+        let byte = match read_byte(input.reader_mut()).map_err(ReadError::Io)? {
+            Some(byte) => byte,
+            None if secret.is_empty() => return Err(ReadError::Eof),
+            None => break,
+        };
+
+        match byte {
+            b'\r' | b'\n' => break,
+            0x03 => return Err(ReadError::Interrupted),
+            0x04 if secret.is_empty() => return Err(ReadError::Eof),
+            0x7f | 0x08 => {
+                if secret.pop_char() {
+                    erase(input.writer_mut(), mask, 1)?;
+                }
+            }
+            0x15 => {
+                let count = secret.char_count();
+                secret.truncate(0);
+                erase(input.writer_mut(), mask, count)?;
+            }
+            0x1a | 0x1c => {
+                let signal = match byte {
+                    0x1a => Signal::Stop,
+                    _ => Signal::Quit,
+                };
+                raw.raise(signal).map_err(ReadError::Io)?;
+
+                let masks: String = match mask {
+                    Some(mask) => std::iter::repeat(mask).take(secret.char_count()).collect(),
+                    None => String::new(),
+                };
+                write!(input.writer_mut(), "{}{}", prompt, masks).map_err(ReadError::Io)?;
+                input.writer_mut().flush().map_err(ReadError::Io)?;
+            }
+            0x1b => skip_escape(input.reader_mut(), raw).map_err(ReadError::Io)?,
+            byte if byte < 0x20 => {}
+            byte => {
+                secret.push(byte);
+                // Print one mask per character, not per UTF-8 byte.
+                if let (Some(mask), false) = (mask, is_continuation(byte)) {
+                    write!(input.writer_mut(), "{}", mask).map_err(ReadError::Io)?;
+                    input.writer_mut().flush().map_err(ReadError::Io)?;
+                }
+            }
+        }
+    }
+
+    Ok(secret)
+}
+
+/// Read a single byte, or `None` at end-of-input.
+pub(crate) fn read_byte<R: BufRead>(reader: &mut R) -> io::Result<Option<u8>> {
+    let byte = reader.fill_buf()?.first().copied();
+    if byte.is_some() {
+        reader.consume(1);
+    }
+
+    Ok(byte)
+}
+
+/// Skip the rest of an escape sequence such as an arrow key.
+///
+/// A lone Escape key is not followed by anything, so only wait briefly for
+/// the next byte, and leave it unread unless it starts a sequence.
+fn skip_escape<R: BufRead>(reader: &mut R, raw: &RawMode) -> io::Result<()> {
+    let next = raw.with_timeout(|| Ok(reader.fill_buf()?.first().copied()))?;
+
+    if let Some(b'[' | b'O') = next {
+        reader.consume(1);
+        while let Some(byte) = read_byte(reader)? {
+            if (0x40..=0x7e).contains(&byte) {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Erase `count` mask characters from the terminal, if masking.
+fn erase<W: Write>(writer: &mut W, mask: Option<char>, count: usize) -> Result<(), ReadError> {
+    if mask.is_some() && count > 0 {
+        write!(writer, "{}", "\x08 \x08".repeat(count)).map_err(ReadError::Io)?;
+        writer.flush().map_err(ReadError::Io)?;
+    }
+
+    Ok(())
+}
+
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xc0 == 0x80
+}
+
+/// A string holding secret text, wiped from memory when dropped.
+///
+/// The buffer is overwritten with zeros on drop, and whenever it has to grow
+/// the old allocation is wiped before it is freed. `Debug` and `Display`
+/// print `[redacted]` instead of the contents, so the secret does not end up
+/// in logs by accident. Use [`expose`](SecretString::expose) to access the
+/// text.
+///
+/// Secret reads collect input directly into this buffer and trim it in
+/// place, so the text is never copied into an intermediate `String`.
+///
+/// ## Example
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Input, Secret, SecretString};
+///
+/// let mut input = Input::new(Cursor::new("  hunter2 \n"), std::io::sink());
+/// let password: SecretString = Secret::new("Password: ").read_from(&mut input).unwrap();
+///
+/// assert_eq!(password.expose(), "hunter2");
+/// assert_eq!(format!("{:?}", password), "[redacted]");
+/// ```
+pub struct SecretString {
+    /// UTF-8 bytes. May hold an incomplete character only while reading.
+    bytes: Vec<u8>,
+}
+
+impl SecretString {
+    /// Initial capacity, large enough for most secrets to never reallocate.
+    const CAPACITY: usize = 64;
+
+    /// Create an empty secret.
+    pub fn new() -> Self {
+        SecretString {
+            bytes: Vec::with_capacity(Self::CAPACITY),
+        }
+    }
+
+    /// Access the secret text.
+    pub fn expose(&self) -> &str {
+        std::str::from_utf8(&self.bytes).unwrap_or_default()
+    }
+
+    /// The length of the secret in bytes.
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Whether the secret is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Parse the secret into `T` with `FromStr`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns `ReadError::Parse` if parsing fails. The error's `input` does
+    /// not contain the secret.
+    pub fn parse<T>(&self) -> Result<T, ReadError>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.expose()
+            .parse::<T>()
+            .map_err(|err| ReadError::parse(HIDDEN, err))
+    }
+
+    /// Append a byte, wiping the old buffer if it has to grow.
+    fn push(&mut self, byte: u8) {
+        if self.bytes.len() == self.bytes.capacity() {
+            let mut grown = Vec::with_capacity((self.bytes.capacity() * 2).max(Self::CAPACITY));
+            grown.extend_from_slice(&self.bytes);
+            zeroize(&mut self.bytes);
+            self.bytes = grown;
+        }
+
+        self.bytes.push(byte);
+    }
+
+    /// Remove the last UTF-8 character, returning whether one was removed.
+    fn pop_char(&mut self) -> bool {
+        match self.bytes.iter().rposition(|&byte| !is_continuation(byte)) {
+            Some(start) => {
+                self.truncate(start);
+                true
+            }
+            None => {
+                self.truncate(0);
+                false
+            }
+        }
+    }
+
+    /// The number of characters, counting incomplete ones.
+    fn char_count(&self) -> usize {
+        self.bytes
+            .iter()
+            .filter(|&&byte| !is_continuation(byte))
+            .count()
+    }
+
+    /// Shorten the secret to `len` bytes, wiping the removed bytes.
+    fn truncate(&mut self, len: usize) {
+        if len < self.bytes.len() {
+            zeroize(&mut self.bytes[len..]);
+            self.bytes.truncate(len);
+        }
+    }
+
+    /// Check that the secret is valid UTF-8 and trim it in place.
+    fn finish(mut self) -> Result<Self, ReadError> {
+        let (start, len) = match std::str::from_utf8(&self.bytes) {
+            Ok(text) => {
+                let trimmed = text.trim();
+                (
+                    trimmed.as_ptr() as usize - text.as_ptr() as usize,
+                    trimmed.len(),
+                )
+            }
+            Err(err) => {
+                return Err(ReadError::Io(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    err,
+                )))
+            }
+        };
+
+        self.bytes.copy_within(start..start + len, 0);
+        self.truncate(len);
+        Ok(self)
+    }
+}
+
+impl Default for SecretString {
+    fn default() -> Self {
+        SecretString::new()
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        zeroize(&mut self.bytes);
+    }
+}
+
+impl FromStr for SecretString {
+    type Err = Infallible;
+
+    /// Copy `text` into a new secret buffer.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut secret = SecretString {
+            bytes: Vec::with_capacity(text.len().max(Self::CAPACITY)),
+        };
+        secret.bytes.extend_from_slice(text.as_bytes());
+        Ok(secret)
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+impl fmt::Display for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[redacted]")
+    }
+}
+
+/// Overwrite `bytes` with zeros in a way the compiler cannot optimize out.
+fn zeroize(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // SAFETY: `byte` is a valid, aligned, exclusive reference.
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Read a secret by feeding `keys` to `read_hidden`, returning the secret
+    /// and what was printed.
+    fn hidden(keys: &str, mask: Option<char>) -> (Result<String, ReadError>, String) {
+        let mut input = Input::new(Cursor::new(keys.as_bytes()), Vec::new());
+        let result = read_hidden(&mut input, "> ", mask, &RawMode::inert())
+            .map(|secret| secret.expose().to_string());
+        let output = String::from_utf8_lossy(input.writer()).into_owned();
+
+        (result, output)
+    }
+
+    #[test]
+    fn masks_are_printed_per_character() {
+        let (secret, output) = hidden("pä€\r", Some('*'));
+        assert_eq!(secret.unwrap(), "pä€");
+        assert_eq!(output, "***");
+
+        let (secret, output) = hidden("pä€\r", None);
+        assert_eq!(secret.unwrap(), "pä€");
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn backspace_removes_a_whole_character() {
+        let (secret, output) = hidden("aé\x7f\r", Some('*'));
+        assert_eq!(secret.unwrap(), "a");
+        assert_eq!(output, "**\x08 \x08");
+
+        // Nothing is erased when there is nothing to remove.
+        let (secret, output) = hidden("\x7fa\r", Some('*'));
+        assert_eq!(secret.unwrap(), "a");
+        assert_eq!(output, "*");
+    }
+
+    #[test]
+    fn ctrl_u_erases_every_mask() {
+        let (secret, output) = hidden("aéc\x15d\r", Some('*'));
+        assert_eq!(secret.unwrap(), "d");
+        assert_eq!(output, format!("***{}*", "\x08 \x08".repeat(3)));
+    }
+
+    #[test]
+    fn control_keys() {
+        assert!(matches!(
+            hidden("ab\x03", None).0,
+            Err(ReadError::Interrupted)
+        ));
+        assert!(matches!(hidden("\x04", None).0, Err(ReadError::Eof)));
+        assert!(matches!(hidden("", None).0, Err(ReadError::Eof)));
+
+        // Ctrl-D after some text, and other control bytes, are ignored.
+        assert_eq!(hidden("a\x04\tb\r", None).0.unwrap(), "ab");
+        assert_eq!(hidden("ab", None).0.unwrap(), "ab");
+    }
+
+    #[test]
+    fn prompt_is_printed_again_after_suspending() {
+        let (secret, output) = hidden("ab\x1acd\r", Some('*'));
+        assert_eq!(secret.unwrap(), "abcd");
+        assert_eq!(output, "**> ****");
+    }
+
+    #[test]
+    fn escape_sequences_are_skipped() {
+        assert_eq!(hidden("a\x1b[Db\r", None).0.unwrap(), "ab");
+        assert_eq!(hidden("a\x1bOAb\r", None).0.unwrap(), "ab");
+        assert_eq!(hidden("a\x1b[3~b\r", None).0.unwrap(), "ab");
+    }
+
+    #[test]
+    fn lone_escape_keeps_the_next_byte() {
+        assert_eq!(hidden("ab\x1bcd\r", None).0.unwrap(), "abcd");
+        assert_eq!(hidden("ab\x1b\r", None).0.unwrap(), "ab");
+    }
+}
@@ -0,0 +1,534 @@
+use std::fmt::Display;
+use std::io::{BufRead, Write};
+
+use crate::fuzzy::fuzzy_rank;
+use crate::menu::Menu;
+use crate::prompt::Retry;
+use crate::term::RawMode;
+use crate::{Input, ReadError};
+
+/// A single-choice menu over a list of items.
+///
+/// The items are printed as a numbered list, then the prompt asks for a
+/// choice. The user can enter the item's number, or its text: matching is
+/// case-insensitive, and any unique prefix of an item is accepted. If an
+/// item's text is exactly the input, that item is chosen even if the input
+/// is also a valid number. Invalid choices are reported and re-prompted.
+///
+/// When [`Input::stdin`] reads from and prompts on a terminal, an
+/// interactive menu is shown instead: the highlight is moved with the arrow keys (or
+/// Ctrl-P/Ctrl-N, Page Up/Down, Home/End) and Enter chooses the highlighted
+/// item. Typing filters the list with [`fuzzy_match`](crate::fuzzy_match),
+/// best matches first, with the matched characters highlighted; Backspace
+/// and Ctrl-U edit the filter. Long lists scroll, and Ctrl-C or Escape
+/// returns `ReadError::Interrupted`. Ctrl-Z and Ctrl-\ suspend and quit
+/// the program as usual. The terminal is restored when the menu ends. The returned index always refers to the original `items`.
+///
+/// ## Example
+///
+/// ```no_run
+/// use tinyinput::Select;
+///
+/// let (index, color) = Select::new("Color: ", &["red", "green", "blue"]).read().unwrap();
+/// ```
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Input, Select};
+///
+/// let mut input = Input::new(Cursor::new("4\nx\ngr\n"), Vec::new());
+/// let choice = Select::new("Color: ", &["red", "green", "blue"])
+///     .read_from(&mut input)
+///     .unwrap();
+///
+/// assert_eq!(choice, (1, &"green"));
+/// assert_eq!(
+///     String::from_utf8_lossy(input.writer()),
+///     "1) red\n2) green\n3) blue\n\
+///      Color: choose a number between 1 and 3\n\
+///      Color: 'x' is not an option\n\
+///      Color: ",
+/// );
+/// ```
+///
+/// Numeric items can be chosen by their text:
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Input, Select};
+///
+/// let mut input = Input::new(Cursor::new("20\n"), std::io::sink());
+/// let choice = Select::new("Amount: ", &[10, 20, 30]).read_from(&mut input).unwrap();
+///
+/// assert_eq!(choice, (1, &20));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Select<'a, T> {
+    text: &'a str,
+    items: &'a [T],
+    retry: Retry,
+    page_size: usize,
+}
+
+impl<'a, T> Select<'a, T>
+where
+    T: Display,
+{
+    /// Create a menu over `items` that prints `text` before reading.
+    ///
+    /// Invalid choices are re-prompted until a valid one is entered.
+    pub fn new(text: &'a str, items: &'a [T]) -> Self {
+        let mut retry = Retry::new();
+        retry.forever();
+
+        Select {
+            text,
+            items,
+            retry,
+            page_size: PAGE_SIZE,
+        }
+    }
+
+    /// Show at most `page_size` items at once in the interactive menu.
+    ///
+    /// Longer lists scroll. The default is 10 items, and the menu never
+    /// grows taller than the terminal.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Give up after `attempts` invalid choices.
+    ///
+    /// At least one attempt is always made. When the last attempt fails, its
+    /// error is returned.
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.retry.limit(attempts);
+        self
+    }
+
+    /// Set the message printed before re-prompting.
+    ///
+    /// Any `{error}` in `message` is replaced by the error's `Display` text.
+    pub fn error_message(mut self, message: impl Into<String>) -> Self {
+        self.retry.set_message(message.into());
+        self
+    }
+
+    /// Show the menu on `stdout` and read the choice from `stdin`.
+    ///
+    /// Returns the index of the chosen item and the item itself.
+    ///
+    /// ## Errors
+    ///
+    /// - Returns `ReadError::Io` if reading or writing fails.
+    /// - Returns `ReadError::Eof` if `stdin` is closed.
+    /// - Returns `ReadError::Invalid` if there are no items, or if the choice
+    ///   is invalid and no attempts are left.
+    pub fn read(&self) -> Result<(usize, &'a T), ReadError> {
+        self.read_from(&mut Input::stdin())
+    }
+
+    /// Show the menu and read the choice using an existing [`Input`] handle.
+    ///
+    /// Errors are the same as for [`read`](Select::read).
+    pub fn read_from<R, W>(&self, input: &mut Input<R, W>) -> Result<(usize, &'a T), ReadError>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        if self.items.is_empty() {
+            return Err(ReadError::Invalid("there are no options".to_string()));
+        }
+
+        let labels = labels(self.items);
+
+        if input.is_interactive() {
+            let mut raw = RawMode::enable().map_err(ReadError::Io)?;
+            let chosen = Menu::single(self.text, &labels, self.page_size).run(
+                input,
+                &mut raw,
+                |_| Ok(()),
+            )?;
+            return Ok((chosen[0], &self.items[chosen[0]]));
+        }
+
+        print_list(input, &labels)?;
+
+        let index = self.retry.run(input, |input| {
+            let line = input.read_line(self.text)?;
+            choose(line.trim(), &labels)
+        })?;
+
+        Ok((index, &self.items[index]))
+    }
+}
+
+/// A menu for choosing any number of items from a list.
+///
+/// The items are printed as a numbered list, then the prompt asks for the
+/// choices. The input is a list of choices separated by commas or
+/// whitespace, where each choice is:
+///
+/// - a number, such as `3`,
+/// - a range of numbers, such as `2-4`,
+/// - an item's text, or a unique prefix of it, as for [`Select`].
+///
+/// The words `all` and `none` on their own select every item or no item.
+/// An empty line also selects no item. Duplicates are ignored, and invalid
+/// choices are reported and re-prompted.
+///
+/// When [`Input::stdin`] reads from and prompts on a terminal, an
+/// interactive menu is shown instead, like for [`Select`]: Space toggles the highlighted
+/// item and Enter confirms the selection. Typing filters the list; items
+/// stay selected while they are filtered out.
+///
+/// ## Example
+///
+/// ```no_run
+/// use tinyinput::MultiSelect;
+///
+/// let features = ["logging", "metrics", "tracing", "auth"];
+/// let chosen = MultiSelect::new("Enable: ", &features).min(1).read().unwrap();
+/// ```
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Input, MultiSelect};
+///
+/// let features = ["logging", "metrics", "tracing", "auth"];
+/// let mut input = Input::new(Cursor::new("5\n4, 1-2 log\n"), std::io::sink());
+/// let chosen = MultiSelect::new("Enable: ", &features).read_from(&mut input).unwrap();
+///
+/// assert_eq!(chosen, [0, 1, 3]);
+/// ```
+///
+/// Text between commas is first tried as a single choice, so items with
+/// spaces in their text can be chosen:
+///
+/// ```
+/// use std::io::Cursor;
+/// use tinyinput::{Input, MultiSelect};
+///
+/// let cities = ["New York", "Paris", "Newark"];
+/// let mut input = Input::new(Cursor::new("new york, paris\nNew York\n2 - 3\n"), std::io::sink());
+/// let prompt = MultiSelect::new("Cities: ", &cities);
+///
+/// assert_eq!(prompt.read_from(&mut input).unwrap(), [0, 1]);
+/// assert_eq!(prompt.read_from(&mut input).unwrap(), [0]);
+/// assert_eq!(prompt.read_from(&mut input).unwrap(), [1, 2]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiSelect<'a, T> {
+    text: &'a str,
+    items: &'a [T],
+    retry: Retry,
+    page_size: usize,
+    min: usize,
+    max: Option<usize>,
+}
+
+impl<'a, T> MultiSelect<'a, T>
+where
+    T: Display,
+{
+    /// Create a menu over `items` that prints `text` before reading.
+    ///
+    /// Invalid choices are re-prompted until valid ones are entered. By
+    /// default any number of items, including none, may be selected.
+    pub fn new(text: &'a str, items: &'a [T]) -> Self {
+        let mut retry = Retry::new();
+        retry.forever();
+
+        MultiSelect {
+            text,
+            items,
+            retry,
+            page_size: PAGE_SIZE,
+            min: 0,
+            max: None,
+        }
+    }
+
+    /// Show at most `page_size` items at once in the interactive menu.
+    ///
+    /// Longer lists scroll. The default is 10 items, and the menu never
+    /// grows taller than the terminal.
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Require at least `min` items to be selected.
+    ///
+    /// Reading fails up front if `min` is more than the number of items or
+    /// than [`max`](MultiSelect::max), since no choice could be accepted.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tinyinput::{Input, MultiSelect};
+    ///
+    /// let mut input = Input::new(Cursor::new("1\n"), std::io::sink());
+    /// let err = MultiSelect::new("Pick:", &["a", "b", "c"])
+    ///     .min(3)
+    ///     .max(1)
+    ///     .read_from(&mut input)
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(err.to_string(), "at least 3 options are required, but at most 1 may be selected");
+    /// ```
+    pub fn min(mut self, min: usize) -> Self {
+        self.min = min;
+        self
+    }
+
+    /// Allow at most `max` items to be selected.
+    pub fn max(mut self, max: usize) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Give up after `attempts` invalid inputs.
+    ///
+    /// At least one attempt is always made. When the last attempt fails, its
+    /// error is returned.
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.retry.limit(attempts);
+        self
+    }
+
+    /// Set the message printed before re-prompting.
+    ///
+    /// Any `{error}` in `message` is replaced by the error's `Display` text.
+    pub fn error_message(mut self, message: impl Into<String>) -> Self {
+        self.retry.set_message(message.into());
+        self
+    }
+
+    /// Show the menu on `stdout` and read the choices from `stdin`.
+    ///
+    /// Returns the indices of the selected items in ascending order.
+    ///
+    /// ## Errors
+    ///
+    /// - Returns `ReadError::Io` if reading or writing fails.
+    /// - Returns `ReadError::Eof` if `stdin` is closed.
+    /// - Returns `ReadError::Invalid` if the minimum cannot be met with the
+    ///   available items or is above the maximum, or if the input is invalid
+    ///   and no attempts are left.
+    pub fn read(&self) -> Result<Vec<usize>, ReadError> {
+        self.read_from(&mut Input::stdin())
+    }
+
+    /// Show the menu and read the choices using an existing [`Input`]
+    /// handle.
+    ///
+    /// Errors are the same as for [`read`](MultiSelect::read).
+    pub fn read_from<R, W>(&self, input: &mut Input<R, W>) -> Result<Vec<usize>, ReadError>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        if self.min > self.items.len() {
+            return Err(ReadError::Invalid(format!(
+                "at least {} options are required, but there are only {}",
+                self.min,
+                self.items.len()
+            )));
+        }
+
+        if let Some(max) = self.max.filter(|&max| self.min > max) {
+            return Err(ReadError::Invalid(format!(
+                "at least {} {} required, but at most {} may be selected",
+                self.min,
+                if self.min == 1 {
+                    "option is"
+                } else {
+                    "options are"
+                },
+                max
+            )));
+        }
+
+        let labels = labels(self.items);
+
+        if input.is_interactive() && !labels.is_empty() {
+            let mut raw = RawMode::enable().map_err(ReadError::Io)?;
+            return Menu::multi(self.text, &labels, self.page_size).run(
+                input,
+                &mut raw,
+                |chosen| self.check_count(chosen.len()),
+            );
+        }
+
+        print_list(input, &labels)?;
+
+        self.retry.run(input, |input| {
+            let line = input.read_line(self.text)?;
+            let chosen = choose_many(line.trim(), &labels)?;
+            self.check_count(chosen.len())?;
+            Ok(chosen)
+        })
+    }
+
+    fn check_count(&self, count: usize) -> Result<(), ReadError> {
+        if count < self.min {
+            return Err(ReadError::Invalid(format!(
+                "select at least {} {}",
+                self.min,
+                options(self.min)
+            )));
+        }
+
+        match self.max {
+            Some(max) if count > max => Err(ReadError::Invalid(format!(
+                "select at most {} {}",
+                max,
+                options(max)
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn options(count: usize) -> &'static str {
+    if count == 1 {
+        "option"
+    } else {
+        "options"
+    }
+}
+
+/// Resolve a list of choices to sorted, deduplicated indices into `labels`.
+fn choose_many(line: &str, labels: &[String]) -> Result<Vec<usize>, ReadError> {
+    match line.to_lowercase().as_str() {
+        "" | "none" => return Ok(Vec::new()),
+        "all" => return Ok((0..labels.len()).collect()),
+        _ => {}
+    }
+
+    let mut chosen = Vec::new();
+
+    // Each comma-separated part is tried as a single choice first, so items
+    // with spaces such as `New York` and ranges such as `2 - 3` work, and is
+    // only split at whitespace if that fails.
+    for part in line.split(',').map(str::trim) {
+        if part.is_empty() {
+            continue;
+        }
+
+        match choose_token(part, labels) {
+            Ok(indices) => chosen.extend(indices),
+            Err(err) if !part.contains(char::is_whitespace) => return Err(err),
+            Err(_) => {
+                for token in part.split_whitespace() {
+                    chosen.extend(choose_token(token, labels)?);
+                }
+            }
+        }
+    }
+
+    chosen.sort_unstable();
+    chosen.dedup();
+    Ok(chosen)
+}
+
+/// Resolve a single choice, which may be a range, to indices into `labels`.
+fn choose_token(token: &str, labels: &[String]) -> Result<Vec<usize>, ReadError> {
+    match parse_range(token) {
+        Some((start, end)) => {
+            if start == 0 || start > end || end > labels.len() {
+                return Err(ReadError::Invalid(format!(
+                    "'{}' is not a range between 1 and {}",
+                    token,
+                    labels.len()
+                )));
+            }
+            Ok((start - 1..end).collect())
+        }
+        None => Ok(vec![choose(token, labels)?]),
+    }
+}
+
+/// Parse `a-b` where both ends are numbers.
+fn parse_range(token: &str) -> Option<(usize, usize)> {
+    let (start, end) = token.split_once('-')?;
+    Some((start.trim().parse().ok()?, end.trim().parse().ok()?))
+}
+
+/// Items shown at once by interactive menus, unless configured otherwise.
+const PAGE_SIZE: usize = 10;
+
+/// The `Display` text of every item.
+pub(crate) fn labels<T: Display>(items: &[T]) -> Vec<String> {
+    items.iter().map(ToString::to_string).collect()
+}
+
+/// Print the numbered list of items for line-based menus.
+fn print_list<R, W>(input: &mut Input<R, W>, labels: &[String]) -> Result<(), ReadError>
+where
+    R: BufRead,
+    W: Write,
+{
+    for (index, label) in labels.iter().enumerate() {
+        writeln!(input.writer_mut(), "{}) {}", index + 1, label).map_err(ReadError::Io)?;
+    }
+
+    Ok(())
+}
+
+/// Resolve a choice to an index into `labels`.
+///
+/// `choice` is an item's text (case-insensitive), a 1-based number, or a
+/// prefix of exactly one item's text, tried in that order.
+pub(crate) fn choose(choice: &str, labels: &[String]) -> Result<usize, ReadError> {
+    if choice.is_empty() {
+        return Err(ReadError::Invalid("please choose an option".to_string()));
+    }
+
+    let needle = choice.to_lowercase();
+    let lower: Vec<String> = labels.iter().map(|label| label.to_lowercase()).collect();
+
+    // An item whose text is exactly the input wins over its number, so
+    // numeric items can be chosen by their text.
+    if let Some(index) = lower.iter().position(|label| *label == needle) {
+        return Ok(index);
+    }
+
+    let number = choice.parse::<usize>().ok();
+    if let Some(number @ 1..) = number.filter(|&number| number <= labels.len()) {
+        return Ok(number - 1);
+    }
+
+    let matches: Vec<usize> = (0..labels.len())
+        .filter(|&index| lower[index].starts_with(&needle))
+        .collect();
+
+    match matches.as_slice() {
+        [index] => Ok(*index),
+        [] if number.is_some() => Err(ReadError::Invalid(format!(
+            "choose a number between 1 and {}",
+            labels.len()
+        ))),
+        [] => Err(ReadError::Invalid(
+            match fuzzy_rank(choice, labels).first() {
+                Some((index, _)) => format!(
+                    "'{}' is not an option; did you mean '{}'?",
+                    choice, labels[*index]
+                ),
+                None => format!("'{}' is not an option", choice),
+            },
+        )),
+        _ => {
+            let names: Vec<&str> = matches
+                .iter()
+                .map(|&index| labels[index].as_str())
+                .collect();
+            Err(ReadError::Invalid(format!(
+                "'{}' matches several options: {}",
+                choice,
+                names.join(", ")
+            )))
+        }
+    }
+}
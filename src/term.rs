@@ -0,0 +1,348 @@
+//! Minimal raw-mode terminal support for Linux, without dependencies.
+//!
+//! Raw mode is only used when `stdin` is a terminal, and interactive
+//! drawing only when `stdout` is one too. On other platforms, or when they
+//! are redirected, callers fall back to plain line reading.
+
+use std::io::{self, IsTerminal, Write};
+
+/// Whether `stdin` is a terminal that can be switched into raw mode.
+pub(crate) fn is_terminal() -> bool {
+    sys::SUPPORTED && io::stdin().is_terminal()
+}
+
+/// Whether both `stdin` and `stdout` are terminals, so interactive output
+/// drawn on `stdout` is seen while keys are read in raw mode.
+pub(crate) fn is_interactive() -> bool {
+    is_terminal() && io::stdout().is_terminal()
+}
+
+/// Whether styled output is allowed, following the `NO_COLOR` convention.
+pub(crate) fn color_enabled() -> bool {
+    std::env::var_os("NO_COLOR").map_or(true, |value| value.is_empty())
+}
+
+/// The terminal size as `(columns, rows)`, or `(80, 24)` if unknown.
+pub(crate) fn size() -> (usize, usize) {
+    sys::size().unwrap_or((80, 24))
+}
+
+/// A signal that a key sends in cooked mode, raised by hand in raw mode.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Signal {
+    /// `SIGTSTP`, sent by Ctrl-Z to suspend the process.
+    Stop,
+    /// `SIGQUIT`, sent by Ctrl-\ to quit the process.
+    Quit,
+}
+
+/// Raw mode on `stdin`, restored to the previous settings when dropped.
+///
+/// Raw mode disables echo, line buffering and signal keys, so Ctrl-C arrives
+/// as a byte instead of killing the process. Ctrl-Z and Ctrl-\ keep their
+/// usual effect through [`raise`](RawMode::raise). Because the settings are
+/// restored in `Drop`, they are also restored when a panic unwinds.
+pub(crate) struct RawMode {
+    /// The settings to restore and the raw settings, or `None` if the
+    /// terminal is left alone.
+    modes: Option<(sys::Termios, sys::Termios)>,
+    cursor_hidden: bool,
+}
+
+impl RawMode {
+    /// Switch `stdin` into raw mode.
+    pub(crate) fn enable() -> io::Result<RawMode> {
+        let original = sys::get()?;
+        let raw = sys::raw(&original);
+        sys::set(&raw)?;
+
+        Ok(RawMode {
+            modes: Some((original, raw)),
+            cursor_hidden: false,
+        })
+    }
+
+    /// A handle that leaves the terminal alone, so keys can be read from a
+    /// buffer in tests. Reads get no timeout, so an Escape at the end of the
+    /// buffer is a lone Escape key.
+    #[cfg(test)]
+    pub(crate) fn inert() -> RawMode {
+        RawMode {
+            modes: None,
+            cursor_hidden: false,
+        }
+    }
+
+    /// Hide the cursor on `stdout` until raw mode ends.
+    pub(crate) fn hide_cursor(&mut self) -> io::Result<()> {
+        if self.modes.is_none() {
+            return Ok(());
+        }
+
+        let mut stdout = io::stdout();
+        stdout.write_all(b"\x1b[?25l")?;
+        stdout.flush()?;
+        self.cursor_hidden = true;
+
+        Ok(())
+    }
+
+    /// Restore the terminal and send `signal` to the process, as its key
+    /// would outside raw mode, then switch back to raw mode if the process
+    /// continues.
+    ///
+    /// For Ctrl-Z this returns once the process is resumed, for example by
+    /// `fg`, and the caller should redraw its output.
+    pub(crate) fn raise(&self, signal: Signal) -> io::Result<()> {
+        let Some((original, raw)) = &self.modes else {
+            return Ok(());
+        };
+
+        let mut stdout = io::stdout();
+        if self.cursor_hidden {
+            stdout.write_all(b"\x1b[?25h")?;
+            stdout.flush()?;
+        }
+
+        sys::set(original)?;
+        sys::raise(signal)?;
+        sys::set(raw)?;
+
+        if self.cursor_hidden {
+            stdout.write_all(b"\x1b[?25l")?;
+            stdout.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Run `read` with reads returning no data after a short timeout
+    /// instead of blocking.
+    ///
+    /// Used to tell a lone Escape key apart from an escape sequence.
+    pub(crate) fn with_timeout<T>(&self, read: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+        let Some((_, raw)) = &self.modes else {
+            return read();
+        };
+
+        sys::set(&sys::with_timeout(raw))?;
+        let result = read();
+        sys::set(raw)?;
+
+        result
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if self.cursor_hidden {
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(b"\x1b[?25h");
+            let _ = stdout.flush();
+        }
+        if let Some((original, _)) = &self.modes {
+            let _ = sys::set(original);
+        }
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+))]
+mod sys {
+    use std::io;
+    use std::os::raw::{c_int, c_ulong, c_ushort};
+
+    use super::Signal;
+
+    pub(crate) const SUPPORTED: bool = true;
+
+    const STDIN: c_int = 0;
+    const STDOUT: c_int = 1;
+    const TCSADRAIN: c_int = 1;
+    const TIOCGWINSZ: c_ulong = 0x5413;
+
+    const SIGQUIT: c_int = 3;
+    const SIGTSTP: c_int = 20;
+
+    const BRKINT: u32 = 0o2;
+    const INPCK: u32 = 0o20;
+    const ISTRIP: u32 = 0o40;
+    const ICRNL: u32 = 0o400;
+    const IXON: u32 = 0o2000;
+
+    const ISIG: u32 = 0o1;
+    const ICANON: u32 = 0o2;
+    const ECHO: u32 = 0o10;
+    const IEXTEN: u32 = 0o100000;
+
+    const VTIME: usize = 5;
+    const VMIN: usize = 6;
+
+    /// `struct termios` as laid out by the Linux C libraries.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub(crate) struct Termios {
+        c_iflag: u32,
+        c_oflag: u32,
+        c_cflag: u32,
+        c_lflag: u32,
+        c_line: u8,
+        c_cc: [u8; 32],
+        c_ispeed: u32,
+        c_ospeed: u32,
+    }
+
+    #[repr(C)]
+    struct Winsize {
+        ws_row: c_ushort,
+        ws_col: c_ushort,
+        ws_xpixel: c_ushort,
+        ws_ypixel: c_ushort,
+    }
+
+    extern "C" {
+        fn tcgetattr(fd: c_int, termios: *mut Termios) -> c_int;
+        fn tcsetattr(fd: c_int, optional_actions: c_int, termios: *const Termios) -> c_int;
+        fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+        #[link_name = "raise"]
+        fn raise_signal(signal: c_int) -> c_int;
+    }
+
+    pub(crate) fn size() -> Option<(usize, usize)> {
+        let mut size = Winsize {
+            ws_row: 0,
+            ws_col: 0,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+
+        // SAFETY: `TIOCGWINSZ` writes a `struct winsize` to a valid pointer.
+        let ok = unsafe { ioctl(STDOUT, TIOCGWINSZ, &mut size as *mut Winsize) } == 0
+            // SAFETY: as above, falling back to `stdin` if `stdout` is redirected.
+            || unsafe { ioctl(STDIN, TIOCGWINSZ, &mut size as *mut Winsize) } == 0;
+
+        if ok && size.ws_col > 0 && size.ws_row > 0 {
+            Some((usize::from(size.ws_col), usize::from(size.ws_row)))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn get() -> io::Result<Termios> {
+        let mut termios = Termios {
+            c_iflag: 0,
+            c_oflag: 0,
+            c_cflag: 0,
+            c_lflag: 0,
+            c_line: 0,
+            c_cc: [0; 32],
+            c_ispeed: 0,
+            c_ospeed: 0,
+        };
+
+        // SAFETY: `termios` is a valid, writable `struct termios`.
+        if unsafe { tcgetattr(STDIN, &mut termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(termios)
+    }
+
+    pub(crate) fn set(termios: &Termios) -> io::Result<()> {
+        // SAFETY: `termios` is a valid `struct termios` read by `tcgetattr`.
+        if unsafe { tcsetattr(STDIN, TCSADRAIN, termios) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn raise(signal: Signal) -> io::Result<()> {
+        let signal = match signal {
+            Signal::Stop => SIGTSTP,
+            Signal::Quit => SIGQUIT,
+        };
+
+        // SAFETY: `raise` has no memory-safety requirements.
+        if unsafe { raise_signal(signal) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    /// `original` with echo, line buffering and signal keys turned off.
+    pub(crate) fn raw(original: &Termios) -> Termios {
+        let mut raw = *original;
+
+        raw.c_iflag &= !(BRKINT | INPCK | ISTRIP | ICRNL | IXON);
+        raw.c_lflag &= !(ISIG | ICANON | ECHO | IEXTEN);
+        raw.c_cc[VMIN] = 1;
+        raw.c_cc[VTIME] = 0;
+
+        raw
+    }
+
+    /// `raw` with reads returning after 100ms even if no byte arrived.
+    pub(crate) fn with_timeout(raw: &Termios) -> Termios {
+        let mut timeout = *raw;
+
+        timeout.c_cc[VMIN] = 0;
+        timeout.c_cc[VTIME] = 1;
+
+        timeout
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+)))]
+mod sys {
+    use std::io;
+
+    use super::Signal;
+
+    pub(crate) const SUPPORTED: bool = false;
+
+    #[derive(Clone, Copy)]
+    pub(crate) struct Termios;
+
+    pub(crate) fn get() -> io::Result<Termios> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub(crate) fn set(_: &Termios) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub(crate) fn raise(_: Signal) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub(crate) fn raw(_: &Termios) -> Termios {
+        Termios
+    }
+
+    pub(crate) fn with_timeout(_: &Termios) -> Termios {
+        Termios
+    }
+
+    pub(crate) fn size() -> Option<(usize, usize)> {
+        None
+    }
+}
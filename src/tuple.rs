@@ -0,0 +1,108 @@
+use crate::input::parse;
+use crate::{Position, ReadError};
+
+/// Types that can be built from a fixed number of text fields.
+///
+/// This is implemented for tuples of up to eight elements whose types all
+/// implement `FromStr`, which lets [`read_tuple`](crate::read_tuple) infer
+/// the type of every field from the assignment context.
+///
+/// ## Example
+///
+/// ```
+/// use tinyinput::FromFields;
+///
+/// let (name, age) = <(String, u32)>::from_fields(&["Ann", "42"]).unwrap();
+///
+/// assert_eq!(name, "Ann");
+/// assert_eq!(age, 42);
+///
+/// let err = <(u32,)>::from_fields(&["1", "2"]).unwrap_err();
+/// assert_eq!(err.to_string(), "expected 1 value, found 2");
+/// ```
+pub trait FromFields: Sized {
+    /// The number of fields this type is built from.
+    const LEN: usize;
+
+    /// Parse `fields`, which must contain exactly [`LEN`](FromFields::LEN)
+    /// items.
+    ///
+    /// ## Errors
+    ///
+    /// - Returns `ReadError::Invalid` if the number of fields is wrong.
+    /// - Returns `ReadError::At` with `Position::Field` if a field cannot be
+    ///   parsed.
+    fn from_fields(fields: &[&str]) -> Result<Self, ReadError>;
+}
+
+macro_rules! tuple_from_fields {
+    ($len:expr; $($name:ident $index:tt),+) => {
+        impl<$($name),+> FromFields for ($($name,)+)
+        where
+            $(
+                $name: std::str::FromStr,
+                $name::Err: Into<Box<dyn std::error::Error + Send + Sync>>,
+            )+
+        {
+            const LEN: usize = $len;
+
+            fn from_fields(fields: &[&str]) -> Result<Self, ReadError> {
+                check_len(fields, Self::LEN)?;
+
+                Ok(($(
+                    parse(fields[$index])
+                        .map_err(|err| ReadError::at(Position::Field($index + 1), err))?,
+                )+))
+            }
+        }
+    };
+}
+
+tuple_from_fields!(1; A 0);
+tuple_from_fields!(2; A 0, B 1);
+tuple_from_fields!(3; A 0, B 1, C 2);
+tuple_from_fields!(4; A 0, B 1, C 2, D 3);
+tuple_from_fields!(5; A 0, B 1, C 2, D 3, E 4);
+tuple_from_fields!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+tuple_from_fields!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+tuple_from_fields!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+fn check_len(fields: &[&str], expected: usize) -> Result<(), ReadError> {
+    if fields.len() == expected {
+        Ok(())
+    } else {
+        Err(ReadError::Invalid(format!(
+            "expected {} {}, found {}",
+            expected,
+            values(expected),
+            fields.len()
+        )))
+    }
+}
+
+fn values(count: usize) -> &'static str {
+    if count == 1 {
+        "value"
+    } else {
+        "values"
+    }
+}
+
+/// Split an already-trimmed line into tuple fields.
+///
+/// Surrounding parentheses are removed. If the line contains a comma it is
+/// split on commas, otherwise on whitespace. Fields are trimmed.
+pub(crate) fn split_fields(line: &str) -> Vec<&str> {
+    let line = line
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+        .map_or(line, str::trim);
+
+    if line.is_empty() {
+        Vec::new()
+    } else if line.contains(',') {
+        line.split(',').map(str::trim).collect()
+    } else {
+        line.split_whitespace().collect()
+    }
+}
@@ -0,0 +1,164 @@
+//! Approximate grapheme and display-width handling for the line editor.
+//!
+//! Full Unicode segmentation tables are too large for a tiny crate, so this
+//! covers the cases that matter when editing a line: combining marks,
+//! variation selectors, emoji modifiers and ZWJ sequences stay attached to
+//! their base character, flags (pairs of regional indicators) stay
+//! together, and East Asian wide characters and emoji take two columns.
+
+/// Whether `c` extends the previous grapheme instead of starting a new one.
+fn is_extend(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036F   // combining diacritical marks
+        | 0x0483..=0x0489
+        | 0x0591..=0x05BD
+        | 0x0610..=0x061A
+        | 0x064B..=0x065F
+        | 0x0E31 | 0x0E34..=0x0E3A | 0x0E47..=0x0E4E
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200C..=0x200D // zero width (non-)joiner
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F // variation selectors
+        | 0xFE20..=0xFE2F
+        | 0x1F3FB..=0x1F3FF // emoji skin tone modifiers
+        | 0xE0020..=0xE007F // tags
+        | 0xE0100..=0xE01EF)
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c as u32, 0x1F1E6..=0x1F1FF)
+}
+
+/// The byte index of the grapheme boundary after the one at `index`.
+pub(crate) fn next_boundary(text: &str, index: usize) -> usize {
+    let mut chars = text[index..].char_indices().peekable();
+
+    let first = match chars.next() {
+        Some((_, c)) => c,
+        None => return text.len(),
+    };
+    let mut prev = first;
+
+    if is_regional_indicator(first) {
+        chars.next_if(|&(_, c)| is_regional_indicator(c));
+    }
+
+    while let Some(&(_, c)) = chars.peek() {
+        if !is_extend(c) && prev != '\u{200D}' {
+            break;
+        }
+        prev = c;
+        chars.next();
+    }
+
+    chars
+        .peek()
+        .map_or(text.len(), |&(offset, _)| index + offset)
+}
+
+/// The byte index of the grapheme boundary before `index`.
+pub(crate) fn prev_boundary(text: &str, index: usize) -> usize {
+    let mut boundary = 0;
+
+    while boundary < index {
+        let next = next_boundary(text, boundary);
+        if next >= index {
+            break;
+        }
+        boundary = next;
+    }
+
+    boundary
+}
+
+/// The number of terminal columns a single character occupies.
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x00..=0x1F | 0x7F..=0x9F => 0,
+        _ if is_extend(c) => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F1E6..=0x1F1FF
+        | 0x1F300..=0x1F64F
+        | 0x1F680..=0x1F6FF
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// The number of terminal columns `text` occupies.
+///
+/// ANSI escape sequences take no space. Each grapheme is as wide as its
+/// first character, or two columns for emoji presentation sequences.
+pub(crate) fn width(text: &str) -> usize {
+    let text = strip_ansi(text);
+    let mut total = 0;
+    let mut index = 0;
+
+    while index < text.len() {
+        let next = next_boundary(&text, index);
+        let grapheme = &text[index..next];
+        let mut chars = grapheme.chars();
+        let first = chars.next().map_or(0, char_width);
+
+        total += if grapheme.contains('\u{FE0F}') || grapheme.contains('\u{200D}') {
+            first.max(2)
+        } else {
+            first
+        };
+        index = next;
+    }
+
+    total
+}
+
+/// The end of the longest prefix of `text` that fits in `max` columns, as
+/// a byte index on a grapheme boundary.
+pub(crate) fn fit_end(text: &str, max: usize) -> usize {
+    let mut total = 0;
+    let mut end = 0;
+
+    while end < text.len() {
+        let next = next_boundary(text, end);
+        total += width(&text[end..next]);
+        if total > max {
+            break;
+        }
+        end = next;
+    }
+
+    end
+}
+
+/// `text` without ANSI escape sequences.
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('\x40'..='\x7e').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,114 @@
+//! Built-in validators for [`Prompt::validate`](crate::Prompt::validate).
+//!
+//! Each function returns a closure that accepts a parsed value and returns
+//! `Ok(())` if it is acceptable, or an error message describing the problem.
+//!
+//! ```
+//! use std::io::Cursor;
+//! use tinyinput::{validate, Input, Prompt};
+//!
+//! let mut input = Input::new(Cursor::new("0\n8080\n"), std::io::sink());
+//! let port: u16 = Prompt::new("Port: ")
+//!     .validate(validate::range(1..=65535))
+//!     .retry()
+//!     .read_from(&mut input)
+//!     .unwrap();
+//!
+//! assert_eq!(port, 8080);
+//! ```
+
+use std::fmt::Display;
+use std::ops::{Bound, RangeBounds};
+
+/// Accept values inside `range`.
+///
+/// ```
+/// let check = tinyinput::validate::range(1..=10);
+///
+/// assert!(check(&5).is_ok());
+/// assert_eq!(check(&11).unwrap_err(), "must be between 1 and 10");
+/// ```
+pub fn range<T, B>(range: B) -> impl Fn(&T) -> Result<(), String>
+where
+    T: PartialOrd + Display,
+    B: RangeBounds<T>,
+{
+    move |value| {
+        if range.contains(value) {
+            Ok(())
+        } else {
+            Err(describe_range(&range))
+        }
+    }
+}
+
+/// Accept strings whose length in characters is inside `range`.
+///
+/// ```
+/// let check = tinyinput::validate::length(1..=3);
+///
+/// assert!(check(&"abc").is_ok());
+/// assert_eq!(check(&"").unwrap_err(), "length must be between 1 and 3");
+/// ```
+pub fn length<T, B>(range: B) -> impl Fn(&T) -> Result<(), String>
+where
+    T: AsRef<str>,
+    B: RangeBounds<usize>,
+{
+    move |value| {
+        if range.contains(&value.as_ref().chars().count()) {
+            Ok(())
+        } else {
+            Err(format!("length {}", describe_range(&range)))
+        }
+    }
+}
+
+/// Accept values equal to one of `allowed`.
+///
+/// ```
+/// let check = tinyinput::validate::one_of(["red", "green"]);
+///
+/// assert!(check(&"red").is_ok());
+/// assert_eq!(check(&"blue").unwrap_err(), "must be one of: red, green");
+/// ```
+pub fn one_of<T, I>(allowed: I) -> impl Fn(&T) -> Result<(), String>
+where
+    T: PartialEq + Display,
+    I: IntoIterator<Item = T>,
+{
+    let allowed: Vec<T> = allowed.into_iter().collect();
+
+    move |value| {
+        if allowed.contains(value) {
+            Ok(())
+        } else {
+            let list: Vec<String> = allowed.iter().map(ToString::to_string).collect();
+            Err(format!("must be one of: {}", list.join(", ")))
+        }
+    }
+}
+
+fn describe_range<T, B>(range: &B) -> String
+where
+    T: Display,
+    B: RangeBounds<T>,
+{
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Included(lo), Bound::Included(hi)) => format!("must be between {} and {}", lo, hi),
+        (Bound::Included(lo), Bound::Excluded(hi)) => {
+            format!("must be at least {} and less than {}", lo, hi)
+        }
+        (Bound::Excluded(lo), Bound::Included(hi)) => {
+            format!("must be greater than {} and at most {}", lo, hi)
+        }
+        (Bound::Excluded(lo), Bound::Excluded(hi)) => {
+            format!("must be greater than {} and less than {}", lo, hi)
+        }
+        (Bound::Included(lo), Bound::Unbounded) => format!("must be at least {}", lo),
+        (Bound::Excluded(lo), Bound::Unbounded) => format!("must be greater than {}", lo),
+        (Bound::Unbounded, Bound::Included(hi)) => format!("must be at most {}", hi),
+        (Bound::Unbounded, Bound::Excluded(hi)) => format!("must be less than {}", hi),
+        (Bound::Unbounded, Bound::Unbounded) => "must be any value".to_string(),
+    }
+}